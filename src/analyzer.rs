@@ -0,0 +1,656 @@
+#[cfg(feature = "lindera")]
+use std::path::PathBuf;
+
+use crate::{
+    analyze_tokens,
+    markup::{parse_markup, ruby_token, Piece},
+    near_miss::{find_near_misses, NearMiss},
+    scan::{split_phrases, Location, ScanFailure, ScanMatch, ScanReport},
+    tokenizer::PronunciationTokenizer,
+    AnalyzeOptions, DegreeUnit, Gomamayo, GomamayoResult, LongVowelMode, MoraComparison,
+    ReadingSource, SpellingMode, Token, UnknownPronounciationPolicy,
+};
+#[cfg(feature = "lindera")]
+use crate::{
+    lindera::{LinderaDictionary, LinderaTokenizer, SegmentationMode},
+    user_dictionary::{UserDictionarySource, BUNDLED_USER_DICTIONARY},
+};
+
+#[cfg(feature = "lindera")]
+pub struct Analyzer<T = LinderaTokenizer> {
+    tokenizer: T,
+    options: AnalyzeOptions,
+}
+
+#[cfg(not(feature = "lindera"))]
+pub struct Analyzer<T> {
+    tokenizer: T,
+    options: AnalyzeOptions,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct AnalyzerBuilder {
+    options: AnalyzeOptions,
+    #[cfg(feature = "lindera")]
+    dictionary: LinderaDictionary,
+    #[cfg(feature = "lindera")]
+    dictionary_path: Option<PathBuf>,
+    #[cfg(feature = "lindera")]
+    user_dictionaries: Vec<UserDictionarySource>,
+    #[cfg(feature = "lindera")]
+    segmentation_modes: Vec<SegmentationMode>,
+}
+
+impl AnalyzerBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn options(mut self, options: AnalyzeOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    pub fn unknown_pronounciation(mut self, policy: UnknownPronounciationPolicy) -> Self {
+        self.options.unknown_pronounciation = policy;
+        self
+    }
+
+    pub fn long_vowel(mut self, mode: LongVowelMode) -> Self {
+        self.options.long_vowel = mode;
+        self
+    }
+
+    pub fn reading_source(mut self, source: ReadingSource) -> Self {
+        self.options.reading_source = source;
+        self
+    }
+
+    /// 濁音・半濁音・清音を区別せずに比べるかどうか。
+    pub fn ignore_voicing(mut self, ignore_voicing: bool) -> Self {
+        self.options.ignore_voicing = ignore_voicing;
+        self
+    }
+
+    /// モーラの母音だけ、あるいは子音だけを比べる。
+    pub fn comparison(mut self, comparison: MoraComparison) -> Self {
+        self.options.comparison = comparison;
+        self
+    }
+
+    /// モーラではなく音素の単位で重なりを探し、次数も音素の数で数える。
+    /// 有効にすると [`AnalyzerBuilder::comparison`] と [`AnalyzerBuilder::degree_unit`] は使わない。
+    pub fn phoneme_matching(mut self, phoneme_matching: bool) -> Self {
+        self.options.phoneme_matching = phoneme_matching;
+        self
+    }
+
+    pub fn degree_unit(mut self, unit: DegreeUnit) -> Self {
+        self.options.degree_unit = unit;
+        self
+    }
+
+    /// [`SpellingMode::Orthographic`] にすると四つ仮名などを表記どおりに比べる。
+    pub fn spelling(mut self, spelling: SpellingMode) -> Self {
+        self.options.spelling = spelling;
+        self
+    }
+
+    /// 単語の境界をまたぐ重なりも探すかどうか。
+    pub fn cross_token(mut self, cross_token: bool) -> Self {
+        self.options.cross_token = cross_token;
+        self
+    }
+
+    /// 入力中の注記 (`博麗《はくれい》` のルビと、`|`・`/` による区切り) に従うかどうか。
+    pub fn inline_markup(mut self, inline_markup: bool) -> Self {
+        self.options.inline_markup = inline_markup;
+        self
+    }
+
+    /// 単語の別の読みも考えるかどうか。
+    ///
+    /// [`LinderaTokenizer`] を自分で作って渡す場合は、辞書の別の読みを集めるように
+    /// [`LinderaTokenizer::alternative_readings`] も有効にすること。
+    pub fn alternative_readings(mut self, alternative_readings: bool) -> Self {
+        self.options.alternative_readings = alternative_readings;
+        self
+    }
+
+    /// 表層形 `surface` の別の読みを追加し、別の読みを考えるようにする。
+    pub fn extra_reading(mut self, surface: impl Into<String>, reading: impl Into<String>) -> Self {
+        self.options
+            .reading_map
+            .entry(surface.into())
+            .or_default()
+            .push(reading.into());
+        self.alternative_readings(true)
+    }
+
+    /// 任意の分かち書き器を使う [`Analyzer`] を作る。辞書に関する設定は無視される。
+    pub fn build_with_tokenizer<T: PronunciationTokenizer>(self, tokenizer: T) -> Analyzer<T> {
+        Analyzer {
+            tokenizer,
+            options: self.options,
+        }
+    }
+}
+
+#[cfg(feature = "lindera")]
+impl AnalyzerBuilder {
+    pub fn dictionary(mut self, dictionary: LinderaDictionary) -> Self {
+        self.dictionary = dictionary;
+        self
+    }
+
+    /// 同梱の辞書の代わりに、`path` にあるビルド済みの辞書を読み込む。
+    /// 辞書の種類は [`AnalyzerBuilder::dictionary`] で指定する。
+    pub fn dictionary_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.dictionary_path = Some(path.into());
+        self
+    }
+
+    pub fn user_dictionary(mut self, source: UserDictionarySource) -> Self {
+        self.user_dictionaries.push(source);
+        self
+    }
+
+    pub fn user_dictionary_path(self, path: impl Into<PathBuf>) -> Self {
+        self.user_dictionary(UserDictionarySource::Path(path.into()))
+    }
+
+    pub fn user_dictionary_csv(self, csv: impl Into<String>) -> Self {
+        self.user_dictionary(UserDictionarySource::Csv(csv.into()))
+    }
+
+    pub fn user_word(self, surface: impl Into<String>, pronounciation: impl Into<String>) -> Self {
+        self.user_dictionary(UserDictionarySource::Words(vec![(
+            surface.into(),
+            pronounciation.into(),
+        )]))
+    }
+
+    /// 分かち書きの候補を作るモード。複数指定すると [`Analyzer::analyze_candidates`] と
+    /// [`Analyzer::analyze_best`] でそれぞれのモードの結果を比べられる。既定では
+    /// [`SegmentationMode::Decompose`] だけを使う。
+    pub fn segmentation_modes(mut self, modes: impl IntoIterator<Item = SegmentationMode>) -> Self {
+        self.segmentation_modes = modes.into_iter().collect();
+        self
+    }
+
+    /// 同梱のユーザー辞書と追加のユーザー辞書をすべて連結した CSV を作る。
+    fn merged_user_dictionary(&self) -> GomamayoResult<String> {
+        let mut merged = String::new();
+        for csv in std::iter::once(Ok(BUNDLED_USER_DICTIONARY.to_string()))
+            .chain(self.user_dictionaries.iter().map(|source| source.to_csv()))
+        {
+            merged.push_str(&csv?);
+            if !merged.is_empty() && !merged.ends_with('\n') {
+                merged.push('\n');
+            }
+        }
+
+        Ok(merged)
+    }
+
+    pub fn build(self) -> GomamayoResult<Analyzer> {
+        let user_dictionary = self.merged_user_dictionary()?;
+        let tokenizer = LinderaTokenizer::with_modes(
+            self.dictionary,
+            self.dictionary_path.as_deref(),
+            &user_dictionary,
+            &self.segmentation_modes,
+        )?
+        .alternative_readings(self.options.alternative_readings);
+
+        Ok(self.build_with_tokenizer(tokenizer))
+    }
+}
+
+#[cfg(feature = "lindera")]
+impl Analyzer {
+    pub fn builder() -> AnalyzerBuilder {
+        AnalyzerBuilder::new()
+    }
+
+    /// 同梱の辞書を使う。辞書を同梱していない場合は [`crate::GomamayoError::DictionaryNotBundledError`]
+    /// を返すので、[`AnalyzerBuilder::dictionary_path`] を指定して構築すること。
+    pub fn new() -> GomamayoResult<Self> {
+        Self::builder().build()
+    }
+}
+
+impl<T: PronunciationTokenizer> Analyzer<T> {
+    pub fn with_tokenizer(tokenizer: T) -> Self {
+        AnalyzerBuilder::new().build_with_tokenizer(tokenizer)
+    }
+
+    pub fn tokenizer(&self) -> &T {
+        &self.tokenizer
+    }
+
+    pub fn options(&self) -> &AnalyzeOptions {
+        &self.options
+    }
+
+    pub fn analyze(&self, input: &str) -> GomamayoResult<Gomamayo> {
+        analyze_tokens(self.tokenize(input)?, &self.options)
+    }
+
+    /// [`AnalyzeOptions::inline_markup`] が有効なら、注記に従って分かち書きする。
+    pub fn tokenize(&self, input: &str) -> GomamayoResult<Vec<Token>> {
+        if self.options.inline_markup {
+            let mut candidates = self.tokenize_with_markup(input, false)?;
+            Ok(candidates.swap_remove(0))
+        } else {
+            self.tokenizer.tokenize(input)
+        }
+    }
+
+    /// 注記を取り除いた各部分を分かち書きしてつなげる。単語の位置は元の入力の中での位置になる。
+    ///
+    /// `all_candidates` が真なら、各部分の k 番目の候補 (無ければ最初の候補) をつなげたものを
+    /// k 番目の候補とする。
+    fn tokenize_with_markup(
+        &self,
+        input: &str,
+        all_candidates: bool,
+    ) -> GomamayoResult<Vec<Vec<Token>>> {
+        let mut pieces = vec![];
+        for piece in parse_markup(input) {
+            let candidates = match piece {
+                Piece::Text { text, span } => {
+                    let candidates = if all_candidates {
+                        self.tokenizer.tokenize_candidates(text)?
+                    } else {
+                        vec![self.tokenizer.tokenize(text)?]
+                    };
+                    candidates
+                        .into_iter()
+                        .map(|tokens| {
+                            tokens
+                                .into_iter()
+                                .map(|token| Token {
+                                    span: token.span.shifted(&span),
+                                    ..token
+                                })
+                                .collect::<Vec<_>>()
+                        })
+                        .collect::<Vec<_>>()
+                }
+                Piece::Ruby {
+                    base,
+                    reading,
+                    span,
+                } => vec![vec![ruby_token(base, reading, span)]],
+            };
+            pieces.push(candidates);
+        }
+
+        let count = pieces.iter().map(Vec::len).max().unwrap_or(1);
+        let mut candidates: Vec<Vec<Token>> = vec![];
+        for k in 0..count {
+            let tokens = pieces
+                .iter()
+                .filter_map(|piece| piece.get(k).or(piece.first()))
+                .flatten()
+                .cloned()
+                .collect::<Vec<_>>();
+            if !candidates.contains(&tokens) {
+                candidates.push(tokens);
+            }
+        }
+
+        Ok(candidates)
+    }
+
+    /// 分かち書きの候補それぞれについて判定する。結果の `tokens` がその候補の分かち書きになる。
+    pub fn analyze_candidates(&self, input: &str) -> GomamayoResult<Vec<Gomamayo>> {
+        let candidates = if self.options.inline_markup {
+            self.tokenize_with_markup(input, true)?
+        } else {
+            self.tokenizer.tokenize_candidates(input)?
+        };
+
+        candidates
+            .into_iter()
+            .map(|tokens| analyze_tokens(tokens, &self.options))
+            .collect()
+    }
+
+    /// 分かち書きの候補のうち、次数が最も高く、次に項数が最も多い判定を返す。
+    /// 同じものがあれば先の候補を選ぶ。
+    pub fn analyze_best(&self, input: &str) -> GomamayoResult<Gomamayo> {
+        let candidates = self.analyze_candidates(input)?;
+        let best = candidates
+            .into_iter()
+            .rev()
+            .max_by_key(|gomamayo| gomamayo.kind.as_ref().map(|kind| (kind.degree, kind.ary)));
+
+        match best {
+            Some(best) => Ok(best),
+            None => self.analyze(input),
+        }
+    }
+
+    /// 隣り合う単語の組ごとに、`max_substitutions` 個までの単位 (モーラ・音節・音素) の食い違いを
+    /// 許した最良の重なり (惜しいゴママヨの候補) を返す。比べ方は [`Analyzer::analyze`] と同じ設定に従う。
+    ///
+    /// 食い違いの無い重なり (本物のゴママヨ) も `substitutions` が 0 の候補として含まれる。
+    /// ただし [`ReadingSource::Both`]・[`ReadingSource::Either`] では発音形だけを比べる。
+    pub fn near_misses(
+        &self,
+        input: &str,
+        max_substitutions: usize,
+    ) -> GomamayoResult<Vec<NearMiss>> {
+        let gomamayo = self.analyze(input)?;
+        Ok(find_near_misses(
+            &gomamayo,
+            &self.options,
+            max_substitutions,
+        ))
+    }
+
+    /// 文章を句読点や改行で句に区切り、ゴママヨを含む句をすべて返す。
+    ///
+    /// 句をまたぐ接合部はゴママヨとはみなさない。分かち書きの候補が複数あれば
+    /// [`Analyzer::analyze_best`] で選んだものを使う。
+    /// 解析できなかった句は [`ScanReport::failures`] に記録して、残りの句を調べ続ける。
+    pub fn scan(&self, text: &str) -> ScanReport {
+        let mut report = ScanReport::default();
+
+        for phrase in split_phrases(text, self.options.inline_markup) {
+            let mut gomamayo = match self.analyze_best(phrase.text) {
+                Ok(gomamayo) => gomamayo,
+                Err(error) => {
+                    report.failures.push(ScanFailure {
+                        line: phrase.line,
+                        column: phrase.column,
+                        span: phrase.span,
+                        text: phrase.text.to_string(),
+                        error,
+                    });
+                    continue;
+                }
+            };
+            if gomamayo.kind.is_none() {
+                continue;
+            }
+
+            gomamayo.shift_spans(&phrase.span);
+            // 句は改行をまたがないので、句の中の文字位置がそのまま列のずれになる
+            let junction_locations = gomamayo
+                .junctions
+                .iter()
+                .map(|junction| Location {
+                    line: phrase.line,
+                    column: phrase.column + junction.left_span.char_start - phrase.span.char_start,
+                })
+                .collect();
+            report.matches.push(ScanMatch {
+                line: phrase.line,
+                column: phrase.column,
+                span: phrase.span,
+                text: phrase.text.to_string(),
+                gomamayo,
+                junction_locations,
+            });
+        }
+
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        kana::is_kana, tests::TEST_CASES, tokenizer::KanaTokenizer, GomamayoError, GomamayoKind,
+    };
+
+    #[cfg(feature = "unidic")]
+    #[test]
+    fn correct_tokenize() {
+        let analyzer = Analyzer::builder()
+            .dictionary(LinderaDictionary::UniDic)
+            .build()
+            .unwrap();
+        for case in TEST_CASES {
+            assert_eq!(
+                analyzer
+                    .tokenize(case.input)
+                    .unwrap()
+                    .iter()
+                    .map(|token| token.pronounciation_or_reading().unwrap())
+                    .collect::<Vec<_>>(),
+                case.expected_pronounciations,
+            );
+        }
+    }
+
+    /// 分かち書きの結果は辞書ごとに異なりうるので、判定結果だけを確かめる。
+    #[cfg(feature = "lindera")]
+    #[test]
+    fn correct_verdict_on_bundled_dictionaries() {
+        for dictionary in [LinderaDictionary::UniDic, LinderaDictionary::Ipadic] {
+            if !dictionary.is_bundled() {
+                continue;
+            }
+
+            let analyzer = Analyzer::builder().dictionary(dictionary).build().unwrap();
+            for case in TEST_CASES {
+                let gomamayo = analyzer.analyze(case.input).unwrap();
+                let ary_degree = gomamayo
+                    .kind
+                    .map(|GomamayoKind { ary, degree }| (ary, degree));
+                assert_eq!(
+                    ary_degree.unwrap_or((0, 0)),
+                    (case.expected_ary, case.expected_degree),
+                    "wrong verdict for {} with {:?}",
+                    case.input,
+                    dictionary
+                );
+            }
+        }
+    }
+
+    #[cfg(feature = "lindera")]
+    #[test]
+    fn analyzer_is_send_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<Analyzer>();
+    }
+
+    #[cfg(feature = "lindera")]
+    #[test]
+    fn unbundled_dictionary_needs_path() {
+        for dictionary in [LinderaDictionary::UniDic, LinderaDictionary::Ipadic] {
+            if dictionary.is_bundled() {
+                continue;
+            }
+
+            assert!(matches!(
+                Analyzer::builder().dictionary(dictionary).build(),
+                Err(GomamayoError::DictionaryNotBundledError(_))
+            ));
+        }
+    }
+
+    #[cfg(any(feature = "unidic", feature = "ipadic"))]
+    #[test]
+    fn user_dictionary_token() {
+        use crate::TokenOrigin;
+
+        let analyzer = Analyzer::new().unwrap();
+        let tokens = analyzer.tokenize("仙狐").unwrap();
+        assert_eq!(tokens.len(), 1);
+        assert_eq!(tokens[0].surface, "仙狐");
+        assert_eq!(tokens[0].origin, TokenOrigin::UserDictionary);
+        assert_eq!(tokens[0].pronounciation_or_reading(), Some("センコ"));
+        assert_eq!(tokens[0].part_of_speech, ["カスタム名詞"]);
+    }
+
+    #[cfg(feature = "lindera")]
+    #[test]
+    fn merge_user_dictionaries() {
+        let builder = Analyzer::builder()
+            .user_dictionary_csv("霊夢,カスタム名詞,レーム")
+            .user_word("サイレンス,スズカ", "サイレンススズカ");
+        assert_eq!(
+            builder.merged_user_dictionary().unwrap(),
+            format!(
+                "{BUNDLED_USER_DICTIONARY}霊夢,カスタム名詞,レーム\n\
+                 \"サイレンス,スズカ\",カスタム名詞,サイレンススズカ\n"
+            )
+        );
+    }
+
+    #[test]
+    fn kana_tokenizer_backend() {
+        let analyzer = Analyzer::with_tokenizer(KanaTokenizer);
+        for case in TEST_CASES {
+            let gomamayo = analyzer
+                .analyze(&case.expected_pronounciations.join("/"))
+                .unwrap();
+            let ary_degree = gomamayo
+                .kind
+                .map(|GomamayoKind { ary, degree }| (ary, degree));
+            assert_eq!(
+                ary_degree.unwrap_or((0, 0)),
+                (case.expected_ary, case.expected_degree),
+                "wrong verdict for {:?}",
+                case.expected_pronounciations
+            );
+        }
+    }
+
+    /// 複合語をまとめる分かち書きと分解する分かち書きの両方を返す
+    struct TwoWayTokenizer;
+
+    impl PronunciationTokenizer for TwoWayTokenizer {
+        fn tokenize(&self, input: &str) -> GomamayoResult<Vec<Token>> {
+            KanaTokenizer.tokenize(&input.replace('/', ""))
+        }
+
+        fn tokenize_candidates(&self, input: &str) -> GomamayoResult<Vec<Vec<Token>>> {
+            Ok(vec![self.tokenize(input)?, KanaTokenizer.tokenize(input)?])
+        }
+    }
+
+    #[test]
+    fn segmentation_candidates() {
+        let analyzer = Analyzer::with_tokenizer(TwoWayTokenizer);
+        let candidates = analyzer.analyze_candidates("ゴマ/マヨ").unwrap();
+        assert_eq!(
+            candidates
+                .iter()
+                .map(|g| g.kind.clone())
+                .collect::<Vec<_>>(),
+            [None, Some(GomamayoKind { ary: 1, degree: 1 })]
+        );
+
+        let best = analyzer.analyze_best("ゴマ/マヨ").unwrap();
+        assert_eq!(best.kind, Some(GomamayoKind { ary: 1, degree: 1 }));
+        assert_eq!(best.tokens.len(), 2);
+
+        // どの候補もゴママヨでなければ先頭の候補を返す
+        let best = analyzer.analyze_best("オレンジ/ジュース").unwrap();
+        assert_eq!(best.tokens.len(), 1);
+    }
+
+    /// カナでない単語には読みを付けない分かち書き器
+    struct SurfaceTokenizer;
+
+    impl PronunciationTokenizer for SurfaceTokenizer {
+        fn tokenize(&self, input: &str) -> GomamayoResult<Vec<Token>> {
+            let mut tokens = KanaTokenizer.tokenize(input)?;
+            for token in &mut tokens {
+                if !is_kana(&token.surface) {
+                    token.pronounciation = None;
+                }
+            }
+            Ok(tokens)
+        }
+    }
+
+    #[test]
+    fn inline_markup() {
+        let analyzer = AnalyzerBuilder::new()
+            .inline_markup(true)
+            .unknown_pronounciation(UnknownPronounciationPolicy::Skip)
+            .build_with_tokenizer(SurfaceTokenizer);
+
+        let input = "博麗《はくれい》霊夢《れいむ》";
+        let gomamayo = analyzer.analyze(input).unwrap();
+        assert_eq!(gomamayo.kind, Some(GomamayoKind { ary: 1, degree: 2 }));
+        assert_eq!(gomamayo.pronounciations, ["ハクレイ", "レイム"]);
+        assert_eq!(&input[gomamayo.tokens[1].span.bytes()], "霊夢");
+        assert_eq!(gomamayo.junctions[0].right_span.chars(), 8..10);
+
+        // 区切りで分けた部分は別々に分かち書きされ、位置は元の入力の中の位置になる
+        let input = "ゴマ|マヨ";
+        let gomamayo = AnalyzerBuilder::new()
+            .inline_markup(true)
+            .build_with_tokenizer(TwoWayTokenizer)
+            .analyze(input)
+            .unwrap();
+        assert_eq!(gomamayo.kind, Some(GomamayoKind { ary: 1, degree: 1 }));
+        assert_eq!(gomamayo.tokens[1].span.chars(), 3..5);
+
+        // 空のルビは読みの分からない単語として扱う
+        let gomamayo = analyzer.analyze("胡麻《》マヨ").unwrap();
+        assert_eq!(gomamayo.skipped, [0]);
+        assert!(matches!(
+            AnalyzerBuilder::new()
+                .inline_markup(true)
+                .build_with_tokenizer(SurfaceTokenizer)
+                .analyze("胡麻《》マヨ"),
+            Err(GomamayoError::UnknownPronounciationError(_))
+        ));
+
+        let matches = analyzer
+            .scan("今日は、博麗《はくれい》霊夢《れいむ》")
+            .matches;
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].gomamayo.tokens[0].span.chars(), 4..6);
+    }
+
+    #[test]
+    fn scan_text() {
+        let analyzer = Analyzer::with_tokenizer(SurfaceTokenizer);
+        let text = "ヤア/ゴマ/マヨ、オレンジ/ジュース、😀\nタイコ/コーボ/ボシュー";
+        let ScanReport { matches, failures } = analyzer.scan(text);
+        assert_eq!(
+            matches
+                .iter()
+                .map(|m| (m.text.as_str(), m.line, m.column))
+                .collect::<Vec<_>>(),
+            [("ヤア/ゴマ/マヨ", 1, 1), ("タイコ/コーボ/ボシュー", 2, 1)]
+        );
+        // 接合部ごとの位置は左側の単語の開始位置
+        assert_eq!(
+            matches
+                .iter()
+                .map(|m| m
+                    .junction_locations
+                    .iter()
+                    .map(|l| (l.line, l.column))
+                    .collect::<Vec<_>>())
+                .collect::<Vec<_>>(),
+            [vec![(1, 4)], vec![(2, 1), (2, 5)]]
+        );
+
+        let junction = &matches[1].gomamayo.junctions[0];
+        assert_eq!(&text[junction.left_span.bytes()], "タイコ");
+        assert_eq!(&text[junction.right_span.bytes()], "コーボ");
+
+        // 読みの分からない句があっても残りの句は調べ続ける
+        assert_eq!(
+            failures
+                .iter()
+                .map(|f| (f.text.as_str(), f.line, f.column))
+                .collect::<Vec<_>>(),
+            [("😀", 1, 20)]
+        );
+    }
+}
@@ -0,0 +1,104 @@
+use std::ops::Range;
+
+use crate::Gomamayo;
+
+/// 途切れずに続く接合部のひとまとまり (タイコ|コーボ|ボシュー など)。
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Chain {
+    /// 最初の単語の番号 (`Gomamayo::tokens` の添字)
+    pub token_start: usize,
+    /// 最後の単語の次の番号
+    pub token_end: usize,
+    /// 含まれる接合部の番号 (`Gomamayo::junctions` の添字)
+    pub junctions: Vec<usize>,
+    /// 各接合部の次数
+    pub degrees: Vec<i32>,
+}
+
+impl Chain {
+    pub fn tokens(&self) -> Range<usize> {
+        self.token_start..self.token_end
+    }
+
+    /// 含まれる接合部の数
+    pub fn len(&self) -> usize {
+        self.junctions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.junctions.is_empty()
+    }
+}
+
+impl Gomamayo {
+    /// 接合部を、右側の単語が次の接合部の左側の単語になっているものどうしでまとめる。
+    pub fn chains(&self) -> Vec<Chain> {
+        let mut chains: Vec<Chain> = vec![];
+
+        for (index, junction) in self.junctions.iter().enumerate() {
+            let continues = index > 0 && self.junctions[index - 1].right == junction.left;
+            match chains.last_mut() {
+                Some(chain) if continues => {
+                    chain.token_end = chain.token_end.max(junction.right_end + 1);
+                    chain.junctions.push(index);
+                    chain.degrees.push(junction.degree);
+                }
+                _ => chains.push(Chain {
+                    token_start: junction.left_start,
+                    token_end: junction.right_end + 1,
+                    junctions: vec![index],
+                    degrees: vec![junction.degree],
+                }),
+            }
+        }
+
+        chains
+    }
+
+    /// 最も長いチェーン。同じ長さのものがあれば先に現れるものを返す。
+    pub fn longest_chain(&self) -> Option<Chain> {
+        self.chains()
+            .into_iter()
+            .rev()
+            .max_by_key(|chain| chain.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analyze_pronounciations;
+
+    #[test]
+    fn chains() {
+        let gomamayo = analyze_pronounciations(&["タイコ", "コーボ", "ボシュー", "シューリョー"]);
+        let chains = gomamayo.chains();
+        assert_eq!(chains.len(), 1);
+        assert_eq!(chains[0].tokens(), 0..4);
+        assert_eq!(chains[0].degrees, [1, 1, 2]);
+
+        let gomamayo =
+            analyze_pronounciations(&["タコー", "コージ", "ゴマ", "マヨ", "ヨル", "ルス"]);
+        let chains = gomamayo.chains();
+        assert_eq!(
+            chains
+                .iter()
+                .map(|chain| chain.tokens())
+                .collect::<Vec<_>>(),
+            [0..2, 2..6]
+        );
+        assert_eq!(chains[0].degrees, [2]);
+        assert_eq!(chains[1].junctions, [1, 2, 3]);
+        assert_eq!(gomamayo.longest_chain(), Some(chains[1].clone()));
+
+        assert_eq!(
+            analyze_pronounciations(&["オレンジ", "ジュース"]).longest_chain(),
+            None
+        );
+    }
+
+    #[test]
+    fn longest_chain_prefers_first() {
+        let gomamayo = analyze_pronounciations(&["ゴマ", "マヨ", "ネコ", "コマ"]);
+        assert_eq!(gomamayo.longest_chain().unwrap().tokens(), 0..2);
+    }
+}
@@ -0,0 +1,123 @@
+use icu_normalizer::ComposingNormalizerBorrowed;
+
+/// 直前のカナと組み合わさって一つのモーラになる小書きのカナ
+pub(crate) const SMALL_KANA: &str = "ャュョァィゥェォヮ";
+
+/// 読みを比較できる形にそろえる。
+///
+/// - NFKC 正規化 (半角カナは全角に、濁点・半濁点は直前のカナと合成される)
+/// - ひらがなをカタカナに
+/// - 「ヵ」「ヶ」を「カ」「ケ」に (これらは単独で一つのモーラになる)
+///
+/// ```
+/// assert_eq!(gomamayo::normalize_kana("ｺﾞﾏﾏﾖ"), "ゴママヨ");
+/// assert_eq!(gomamayo::normalize_kana("ごままよ"), "ゴママヨ");
+/// assert_eq!(gomamayo::normalize_kana("ハ゛ス"), "バス");
+/// ```
+pub fn normalize_kana(text: &str) -> String {
+    // 単独の濁点・半濁点は NFKC で空白と結合文字に分解されてしまうので、先に結合文字にしておく
+    let text = text
+        .chars()
+        .map(|c| match c {
+            '゛' => '\u{3099}',
+            '゜' => '\u{309A}',
+            _ => c,
+        })
+        .collect::<String>();
+
+    ComposingNormalizerBorrowed::new_nfkc()
+        .normalize(&text)
+        .chars()
+        .map(|c| match c {
+            'ヵ' | 'ゕ' => 'カ',
+            'ヶ' | 'ゖ' => 'ケ',
+            'ぁ'..='ゔ' | 'ゝ' | 'ゞ' => char::from_u32(c as u32 + 0x60).unwrap_or(c),
+            _ => c,
+        })
+        .collect()
+}
+
+/// 発音の同じカナを一つにそろえる。[`normalize_kana`] を適用してから置き換える。
+///
+/// - 四つ仮名: 「ヂ」を「ジ」に、「ヅ」を「ズ」に
+/// - 歴史的仮名: 「ヲ」「ヰ」「ヱ」を「オ」「イ」「エ」に
+///
+/// ```
+/// assert_eq!(gomamayo::phonetic_kana("ハナヂ"), "ハナジ");
+/// assert_eq!(gomamayo::phonetic_kana("ゐなか"), "イナカ");
+/// ```
+pub fn phonetic_kana(text: &str) -> String {
+    normalize_kana(text)
+        .chars()
+        .map(|c| match c {
+            'ヂ' => 'ジ',
+            'ヅ' => 'ズ',
+            'ヲ' => 'オ',
+            'ヰ' => 'イ',
+            'ヱ' => 'エ',
+            _ => c,
+        })
+        .collect()
+}
+
+/// カナ (と長音記号) だけからなる文字列かどうか。
+pub(crate) fn is_kana(text: &str) -> bool {
+    !text.is_empty()
+        && text
+            .chars()
+            .all(|c| matches!(c, 'ァ'..='ヺ' | 'ー' | 'ヽ' | 'ヾ'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hiragana_to_katakana() {
+        assert_eq!(normalize_kana("しゃわー"), "シャワー");
+        assert_eq!(normalize_kana("ゔぁいおりん"), "ヴァイオリン");
+        assert_eq!(normalize_kana("くゎし"), "クヮシ");
+    }
+
+    #[test]
+    fn half_width_to_full_width() {
+        assert_eq!(normalize_kana("ｼｬﾜｰ"), "シャワー");
+        assert_eq!(normalize_kana("ﾊﾟﾊﾟｲﾔ"), "パパイヤ");
+        assert_eq!(normalize_kana("ｳﾞｧｲｵﾘﾝ"), "ヴァイオリン");
+    }
+
+    #[test]
+    fn combining_sound_marks() {
+        assert_eq!(normalize_kana("カ\u{3099}ッコウ"), "ガッコウ");
+        assert_eq!(normalize_kana("ハ゜ン"), "パン");
+        assert_eq!(normalize_kana("ハ゛ス"), "バス");
+    }
+
+    #[test]
+    fn standalone_small_kana() {
+        assert_eq!(normalize_kana("ヵヶゕゖ"), "カケカケ");
+    }
+
+    #[test]
+    fn yotsugana() {
+        assert_eq!(phonetic_kana("ヂ"), phonetic_kana("ジ"));
+        assert_eq!(phonetic_kana("ぢゃ"), "ジャ");
+        assert_eq!(phonetic_kana("ヅ"), phonetic_kana("ズ"));
+        assert_eq!(phonetic_kana("ツヅキ"), "ツズキ");
+    }
+
+    #[test]
+    fn historical_kana() {
+        assert_eq!(phonetic_kana("ヲ"), phonetic_kana("オ"));
+        assert_eq!(phonetic_kana("ヰ"), phonetic_kana("イ"));
+        assert_eq!(phonetic_kana("ゑびす"), "エビス");
+    }
+
+    #[test]
+    fn kana_only() {
+        assert!(is_kana("ゴママヨ"));
+        assert!(!is_kana("ごままよ"));
+        assert!(!is_kana("☆"));
+        assert!(!is_kana(""));
+    }
+}
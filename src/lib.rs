@@ -1,16 +1,38 @@
-use std::io::{self, Write};
+mod analyzer;
+mod chain;
+mod kana;
+#[cfg(feature = "lindera")]
+mod lindera;
+mod markup;
+mod mora;
+mod near_miss;
+mod scan;
+mod tokenizer;
+#[cfg(feature = "lindera")]
+mod user_dictionary;
 
-use itertools::Itertools;
-use lindera_core::{
-    error::LinderaError,
-    mode::{Mode, Penalty},
-};
-use lindera_dictionary::{DictionaryConfig, DictionaryKind, UserDictionaryConfig};
-use lindera_tokenizer::tokenizer::{Tokenizer, TokenizerConfig};
-use tempfile::Builder;
+use std::{cmp::Reverse, collections::BTreeMap, io, ops::Range};
+
+use itertools::{EitherOrBoth, Itertools};
+#[cfg(feature = "lindera")]
+use lindera_core::error::LinderaError;
 
-const LINDERA_DETAIL_READING_COLUMN: usize = 6;
-const LINDERA_DETAIL_PRONOUNCIATION_COLUMN: usize = 9;
+use kana::is_kana;
+use mora::{apply_long_vowel_mode, into_phonemes, syllable_ranges};
+
+pub use analyzer::{Analyzer, AnalyzerBuilder};
+pub use chain::Chain;
+pub use kana::{normalize_kana, phonetic_kana};
+#[cfg(feature = "lindera")]
+pub use lindera::{LinderaDictionary, LinderaTokenizer, SegmentationMode};
+pub use mora::{
+    into_moras, into_syllables, DegreeUnit, LongVowelMode, Mora, MoraComparison, MoraKind,
+};
+pub use near_miss::NearMiss;
+pub use scan::{Location, ScanFailure, ScanMatch, ScanReport};
+pub use tokenizer::{KanaTokenizer, PronunciationTokenizer};
+#[cfg(feature = "lindera")]
+pub use user_dictionary::UserDictionarySource;
 
 pub type GomamayoResult<T, E = GomamayoError> = Result<T, E>;
 
@@ -19,14 +41,34 @@ pub struct UnknownPronounciationError {
     pub text: String,
 }
 
+#[cfg(feature = "lindera")]
+#[derive(Debug)]
+pub struct InvalidUserDictionaryError {
+    pub message: String,
+}
+
+/// 同梱されていない辞書を、パスを指定せずに使おうとした。
+/// [`AnalyzerBuilder::dictionary_path`] でビルド済みの辞書のパスを指定する必要がある。
+#[cfg(feature = "lindera")]
+#[derive(Debug)]
+pub struct DictionaryNotBundledError {
+    pub dictionary: LinderaDictionary,
+}
+
 #[derive(Debug)]
 #[non_exhaustive]
 pub enum GomamayoError {
     IoError(io::Error),
+    #[cfg(feature = "lindera")]
     LinderaError(LinderaError),
     UnknownPronounciationError(UnknownPronounciationError),
+    #[cfg(feature = "lindera")]
+    InvalidUserDictionaryError(InvalidUserDictionaryError),
+    #[cfg(feature = "lindera")]
+    DictionaryNotBundledError(DictionaryNotBundledError),
 }
 
+#[cfg(feature = "lindera")]
 impl From<LinderaError> for GomamayoError {
     fn from(value: LinderaError) -> Self {
         GomamayoError::LinderaError(value)
@@ -42,7 +84,86 @@ impl From<io::Error> for GomamayoError {
 #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct Gomamayo {
     pub kind: Option<GomamayoKind>,
+    /// 各単語の判定に使った読み。読みが分からず飛ばした単語は空文字列になる。
     pub pronounciations: Vec<String>,
+    pub tokens: Vec<Token>,
+    pub junctions: Vec<Junction>,
+    /// 読みが分からず飛ばした単語の番号
+    pub skipped: Vec<usize>,
+    /// 別の読みを使った単語の番号。使った読みは `pronounciations` にある。
+    pub alternatives_used: Vec<usize>,
+}
+
+/// 判定の方法に関する設定。
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct AnalyzeOptions {
+    pub unknown_pronounciation: UnknownPronounciationPolicy,
+    pub long_vowel: LongVowelMode,
+    pub reading_source: ReadingSource,
+    /// 濁音・半濁音・清音を区別せずに比べる (ゆるいゴママヨ)
+    pub ignore_voicing: bool,
+    pub comparison: MoraComparison,
+    /// モーラではなく音素 (ローマ字) の単位で重なりを探し、[`GomamayoKind::degree`] も音素の数で数える。
+    /// このとき [`AnalyzeOptions::comparison`] と [`AnalyzeOptions::degree_unit`] は使わない。
+    pub phoneme_matching: bool,
+    /// 重なりを探す単位と、[`GomamayoKind::degree`] を数える単位。
+    /// [`AnalyzeOptions::phoneme_matching`] が有効なときは使わない。
+    pub degree_unit: DegreeUnit,
+    pub spelling: SpellingMode,
+    /// 単語の境界をまたいでモーラの列を比べ、短い単語を丸ごと覆う重なりも探す
+    pub cross_token: bool,
+    /// 単語の別の読み ([`Token::alternative_pronounciations`] と `reading_map`) も考え、
+    /// 接合部の次数の合計が最も大きくなる読みを選ぶ
+    pub alternative_readings: bool,
+    /// 表層形ごとの追加の読み。`alternative_readings` が有効なときだけ使う。
+    pub reading_map: BTreeMap<String, Vec<String>>,
+    /// 入力中の注記 (`博麗《はくれい》` のルビと、`|`・`/` による区切り) に従う
+    pub inline_markup: bool,
+}
+
+/// 表記の違うカナをどこまで同じものとみなすか。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum SpellingMode {
+    /// 発音の同じカナ (ヂとジ、ヅとズ、ヲとオ、ヰとイ、ヱとエ) を同じものとみなす
+    #[default]
+    Phonetic,
+    /// 表記どおりに比べる
+    Orthographic,
+}
+
+/// 判定に辞書のどの列を使うか。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ReadingSource {
+    /// 発音形 (ハクレー) を使い、無ければ読みを使う
+    #[default]
+    Pronunciation,
+    /// 読み (ハクレイ) を使い、無ければ発音形を使う。UniDic では活用している語の読みは
+    /// 無い ([`Token::reading`]) ので、発音形を使う。
+    Reading,
+    /// 発音形と読みの両方で重なっている場合だけゴママヨとみなす
+    Both,
+    /// 発音形と読みのどちらかで重なっていればゴママヨとみなす
+    Either,
+}
+
+/// 読みの分からない単語 (記号、絵文字、未知語など) の扱い。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum UnknownPronounciationPolicy {
+    /// [`GomamayoError::UnknownPronounciationError`] で判定を中断する
+    #[default]
+    Fail,
+    /// その単語を飛ばす。飛ばした単語の前後はつながっていないものとみなす。
+    Skip,
+    /// 表層形がカナだけならそれを読みとして使い、そうでなければ飛ばす
+    Surface,
+}
+
+impl Gomamayo {
+    /// ゴママヨであり、濁点・半濁点を無視しなければ重ならない接合部を含むかどうか。
+    pub fn is_loose(&self) -> bool {
+        self.junctions.iter().any(|junction| junction.loose)
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
@@ -51,131 +172,710 @@ pub struct GomamayoKind {
     pub degree: i32,
 }
 
-fn tokenize_to_pronounciations(input: &str) -> GomamayoResult<Vec<String>> {
-    // ユーザー辞書を一時ファイルに書き出す (Linderaではファイルを指定する必要があるため)
-    let mut user_jisyo_temp_file = Builder::new().suffix(".csv").tempfile()?;
-    user_jisyo_temp_file.write_all(include_bytes!("./user_jisyo.csv"))?;
+/// 隣り合う単語の読みが重なっている箇所。
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Junction {
+    /// 左側の単語の番号 (`Gomamayo::tokens` の添字)
+    pub left: usize,
+    /// 右側の単語の番号
+    pub right: usize,
+    /// 重なりの左側がかかる最初の単語の番号。[`AnalyzeOptions::cross_token`] が無効なら `left` と同じ。
+    pub left_start: usize,
+    /// 重なりの右側がかかる最後の単語の番号。[`AnalyzeOptions::cross_token`] が無効なら `right` と同じ。
+    pub right_end: usize,
+    /// 重なっているモーラ (音素の単位で探した場合は、重なりに一部でもかかる右側のモーラ)
+    pub moras: Vec<Mora>,
+    /// 重なっているモーラの数
+    pub degree: i32,
+    /// 重なっている音節の数。重なりが音節の途中で始まるか終わる場合は `None` になる。
+    pub syllable_degree: Option<i32>,
+    /// 重なっている音素の数。[`AnalyzeOptions::phoneme_matching`] が有効なときだけ `Some` になる。
+    pub phoneme_degree: Option<i32>,
+    /// 重なりの左側がかかる単語 (`left_start` から `left` まで) の表層形の入力中の位置
+    pub left_span: Span,
+    /// 重なりの右側がかかる単語 (`right` から `right_end` まで) の表層形の入力中の位置
+    pub right_span: Span,
+    /// どちらの読みで重なっていたか。両方で同じだけ重なっていた場合は [`ReadingSource::Both`]。
+    pub source: ReadingSource,
+    /// 濁点・半濁点を無視したときにだけ重なっている (ゆるいゴママヨ)
+    pub loose: bool,
+    /// どの比べ方で重なっていたか。モーラ全体が重なっていれば [`MoraComparison::Exact`] になる。
+    /// `degree` はこの比べ方での次数。
+    pub comparison: MoraComparison,
+}
 
-    let dictionary = DictionaryConfig {
-        kind: Some(DictionaryKind::UniDic),
-        path: None,
-    };
+/// 入力文字列中の範囲。バイト単位と文字単位の両方を持つ。
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Span {
+    pub byte_start: usize,
+    pub byte_end: usize,
+    pub char_start: usize,
+    pub char_end: usize,
+}
+
+impl Span {
+    pub(crate) fn from_byte_range(input: &str, bytes: Range<usize>) -> Self {
+        let char_start = input[..bytes.start].chars().count();
+        let char_end = char_start + input[bytes.clone()].chars().count();
+
+        Span {
+            byte_start: bytes.start,
+            byte_end: bytes.end,
+            char_start,
+            char_end,
+        }
+    }
+
+    /// `self` の先頭から `other` の末尾までの範囲。
+    pub(crate) fn to(&self, other: &Span) -> Span {
+        Span {
+            byte_start: self.byte_start,
+            byte_end: other.byte_end,
+            char_start: self.char_start,
+            char_end: other.char_end,
+        }
+    }
+
+    pub fn bytes(&self) -> Range<usize> {
+        self.byte_start..self.byte_end
+    }
+
+    pub fn chars(&self) -> Range<usize> {
+        self.char_start..self.char_end
+    }
+}
+
+/// 分かち書きされた単語一つ分の情報。
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Token {
+    pub surface: String,
+    /// 発音形 (辞書に無ければ `None`)
+    pub pronounciation: Option<String>,
+    /// 表層形が同じ、辞書の他の項目の発音形
+    pub alternative_pronounciations: Vec<String>,
+    /// 読み (辞書に無ければ `None`)。UniDic には表層形の読みが無く語彙素の読みしか無いので、
+    /// 活用している語では `None` になる。
+    pub reading: Option<String>,
+    /// 品詞 (大分類から順に、`*` の列は除く)
+    pub part_of_speech: Vec<String>,
+    /// 語彙素 (辞書に無ければ `None`)
+    pub lemma: Option<String>,
+    pub origin: TokenOrigin,
+    pub span: Span,
+}
 
-    let user_dictionary = Some(UserDictionaryConfig {
-        kind: Some(DictionaryKind::UniDic),
-        path: user_jisyo_temp_file.path().to_owned(),
-    });
+impl Token {
+    /// 解析に使う読み。発音形を優先し、無ければ読みを使う。
+    pub fn pronounciation_or_reading(&self) -> Option<&str> {
+        self.pronounciation.as_deref().or(self.reading.as_deref())
+    }
+
+    /// 読みを優先し、無ければ発音形を使う。
+    pub fn reading_or_pronounciation(&self) -> Option<&str> {
+        self.reading.as_deref().or(self.pronounciation.as_deref())
+    }
+}
+
+/// 単語がどの辞書から来たか。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum TokenOrigin {
+    SystemDictionary,
+    UserDictionary,
+    /// どの辞書にも無い未知語
+    Unknown,
+    /// 呼び出し側から読みが直接与えられた
+    Provided,
+}
+
+struct Overlap {
+    left: usize,
+    right: usize,
+    left_start: usize,
+    right_end: usize,
+    /// 重なりにかかる左側のモーラの数
+    left_len: usize,
+    moras: Vec<Mora>,
+    /// 重なりが音節の区切りで始まり音節の区切りで終わるときの音節の数
+    syllable_degree: Option<usize>,
+    phoneme_degree: Option<usize>,
+    source: ReadingSource,
+    loose: bool,
+    comparison: MoraComparison,
+}
 
-    let config = TokenizerConfig {
-        dictionary,
-        user_dictionary,
-        mode: Mode::Decompose(Penalty::default()),
+/// 読みを、表記と長音の設定を適用した比較用のモーラの列にする。
+pub(crate) fn comparison_moras(pronounciation: &str, options: &AnalyzeOptions) -> Vec<Mora> {
+    let moras = match options.spelling {
+        SpellingMode::Phonetic => into_moras(&phonetic_kana(pronounciation)),
+        SpellingMode::Orthographic => into_moras(pronounciation),
     };
 
-    let tokenizer = Tokenizer::from_config(config)?;
-    let mut tokens = tokenizer.tokenize(input)?;
-
-    let pronounciations = tokens
-        .iter_mut()
-        .map(|token| {
-            token
-                .get_details()
-                .and_then(|d| {
-                    if let Some(p) = d.get(LINDERA_DETAIL_PRONOUNCIATION_COLUMN) {
-                        if *p != "*" {
-                            return Some(p.to_string());
-                        }
-                    }
+    apply_long_vowel_mode(moras, options.long_vowel)
+}
 
-                    if let Some(r) = d.get(LINDERA_DETAIL_READING_COLUMN) {
-                        if *r != "*" {
-                            return Some(r.to_string());
-                        }
-                    }
+/// 重なりを比べる単位 (モーラ・音節・音素) 一つ分。
+pub(crate) struct Unit {
+    /// この単位がかかるモーラの範囲。音素の場合はその音素を含むモーラ一つ分になる。
+    pub moras: Range<usize>,
+    pub key: Vec<String>,
+}
 
-                    None
-                })
-                .ok_or_else(|| {
-                    GomamayoError::UnknownPronounciationError(UnknownPronounciationError {
-                        text: token.text.to_string(),
-                    })
-                })
+/// モーラの列を [`AnalyzeOptions`] に従って重なりを比べる単位に区切る。
+/// 音素の単位で比べるときは `comparison` を使わない。
+pub(crate) fn comparison_units(
+    moras: &[Mora],
+    options: &AnalyzeOptions,
+    ignore_voicing: bool,
+    comparison: MoraComparison,
+) -> Vec<Unit> {
+    let unvoiced;
+    let moras = if ignore_voicing {
+        unvoiced = moras.iter().map(Mora::unvoiced).collect_vec();
+        &unvoiced[..]
+    } else {
+        moras
+    };
+
+    if options.phoneme_matching {
+        return into_phonemes(moras)
+            .into_iter()
+            .map(|(index, phoneme)| Unit {
+                moras: index..index + 1,
+                key: vec![phoneme],
+            })
+            .collect();
+    }
+
+    let ranges = match options.degree_unit {
+        DegreeUnit::Mora => (0..moras.len()).map(|i| i..i + 1).collect_vec(),
+        DegreeUnit::Syllable => syllable_ranges(moras),
+    };
+    ranges
+        .into_iter()
+        .map(|range| Unit {
+            key: moras[range.clone()]
+                .iter()
+                .map(|mora| mora.comparison_key(comparison))
+                .collect(),
+            moras: range,
         })
-        .collect::<GomamayoResult<Vec<_>, _>>()?;
+        .collect()
+}
 
-    Ok(pronounciations)
+/// 隣り合う単語 `left_index` と `right_index` の接合部で比べる、左右のモーラの列。
+///
+/// 単語をまたぐ場合は前後の単語も連結する。ただし読みの無い (飛ばした) 単語の先にはつながらない。
+pub(crate) fn junction_moras(
+    moras: &[Vec<Mora>],
+    left_index: usize,
+    right_index: usize,
+    options: &AnalyzeOptions,
+) -> (Vec<Mora>, Vec<Mora>) {
+    let (first, last) = if options.cross_token {
+        let first = (0..=left_index)
+            .rev()
+            .take_while(|&i| !moras[i].is_empty())
+            .last()
+            .unwrap_or(left_index);
+        let last = (right_index..moras.len())
+            .take_while(|&i| !moras[i].is_empty())
+            .last()
+            .unwrap_or(right_index);
+        (first, last)
+    } else {
+        (left_index, right_index)
+    };
+
+    (
+        moras[first..=left_index].concat(),
+        moras[right_index..=last].concat(),
+    )
 }
 
-fn into_moras(pronounciation: &str) -> Vec<String> {
-    let mut moras = vec![];
-    let mut curr = String::new();
+/// 左側の末尾 `left_len` モーラと右側の先頭 `right_len` モーラがかかる単語の範囲
+/// (左側の最初の単語と右側の最後の単語の番号)。
+pub(crate) fn covered_tokens(
+    moras: &[Vec<Mora>],
+    left_index: usize,
+    right_index: usize,
+    left_len: usize,
+    right_len: usize,
+) -> (usize, usize) {
+    let mut left_start = left_index;
+    let mut covered = moras[left_index].len();
+    while covered < left_len {
+        left_start -= 1;
+        covered += moras[left_start].len();
+    }
+    let mut right_end = right_index;
+    let mut covered = moras[right_index].len();
+    while covered < right_len {
+        right_end += 1;
+        covered += moras[right_end].len();
+    }
 
-    for c in pronounciation.chars() {
-        if !"ャュョァィゥェォ".contains(c) && !curr.is_empty() {
-            moras.push(curr);
-            curr = String::new();
-        }
+    (left_start, right_end)
+}
 
-        curr.push(c);
+fn find_overlaps<S: AsRef<str>>(
+    pronounciations: &[S],
+    options: &AnalyzeOptions,
+    source: ReadingSource,
+) -> Vec<Overlap> {
+    let mut overlaps = vec![];
+    let moras = pronounciations
+        .iter()
+        .map(|s| comparison_moras(s.as_ref(), options))
+        .collect_vec();
+
+    for (left_index, right_index) in (0..moras.len()).tuple_windows() {
+        let (left, right) = junction_moras(&moras, left_index, right_index, options);
+
+        if let Some(overlap) = find_overlap(&left, &right, options) {
+            let (left_start, right_end) = covered_tokens(
+                &moras,
+                left_index,
+                right_index,
+                overlap.left_len,
+                overlap.moras.len(),
+            );
+
+            overlaps.push(Overlap {
+                left: left_index,
+                right: right_index,
+                left_start,
+                right_end,
+                source,
+                ..overlap
+            });
+        }
     }
 
-    if !curr.is_empty() {
-        moras.push(curr);
+    overlaps
+}
+
+/// 左側の末尾 `degree` 単位と右側の先頭 `degree` 単位が一致するか。
+fn units_overlap(left: &[Unit], right: &[Unit], degree: usize) -> bool {
+    degree <= left.len().min(right.len())
+        && left[left.len() - degree..]
+            .iter()
+            .map(|unit| &unit.key)
+            .eq(right[..degree].iter().map(|unit| &unit.key))
+}
+
+/// 左側の末尾 `degree` 単位と右側の先頭 `degree` 単位の重なりが、モーラの区切りで始まり
+/// モーラの区切りで終わるか。音素の単位で比べるときだけ偽になりうる。
+fn mora_aligned(left: &[Unit], right: &[Unit], degree: usize) -> bool {
+    let first = left.len() - degree;
+    (first == 0 || left[first - 1].moras.end <= left[first].moras.start)
+        && right
+            .get(degree)
+            .is_none_or(|next| next.moras.start >= right[degree - 1].moras.end)
+}
+
+/// [`AnalyzeOptions`] に従って左右のモーラの列の重なりを探す。`left`・`right`・`source` は
+/// 呼び出し側で埋める。
+fn find_overlap(left: &[Mora], right: &[Mora], options: &AnalyzeOptions) -> Option<Overlap> {
+    let units = |ignore_voicing: bool, comparison| {
+        (
+            comparison_units(left, options, ignore_voicing, comparison),
+            comparison_units(right, options, ignore_voicing, comparison),
+        )
+    };
+    let overlaps_by = |degree: usize, ignore_voicing: bool, comparison| {
+        let (left_units, right_units) = units(ignore_voicing, comparison);
+        units_overlap(&left_units, &right_units, degree)
+    };
+
+    let (left_units, right_units) = units(options.ignore_voicing, options.comparison);
+    let degree = (1..=left_units.len().min(right_units.len()))
+        .rev()
+        .find(|&d| units_overlap(&left_units, &right_units, d))?;
+
+    // 音素の単位では常にモーラ全体 (音素) を比べる
+    let comparison = if options.phoneme_matching
+        || overlaps_by(degree, options.ignore_voicing, MoraComparison::Exact)
+    {
+        MoraComparison::Exact
+    } else {
+        options.comparison
+    };
+
+    // 重なりの最初と最後の単位を含むモーラまでを重なっているモーラとする
+    let left_len = left.len() - left_units[left_units.len() - degree].moras.start;
+    let right_len = right_units[degree - 1].moras.end;
+
+    Some(Overlap {
+        left: 0,
+        right: 0,
+        left_start: 0,
+        right_end: 0,
+        left_len,
+        moras: right[..right_len].to_vec(),
+        syllable_degree: syllable_degree(left, right, left_len, right_len)
+            .filter(|_| mora_aligned(&left_units, &right_units, degree)),
+        phoneme_degree: options.phoneme_matching.then_some(degree),
+        source: ReadingSource::default(),
+        loose: options.ignore_voicing && !overlaps_by(degree, false, options.comparison),
+        comparison,
+    })
+}
+
+/// 左側の末尾 `left_len` モーラと右側の先頭 `right_len` モーラの重なりが、両側とも音節の区切りで
+/// 始まり音節の区切りで終わるなら、その音節の数を返す。
+fn syllable_degree(
+    left: &[Mora],
+    right: &[Mora],
+    left_len: usize,
+    right_len: usize,
+) -> Option<usize> {
+    let left_start = left.len() - left_len;
+    let left_aligned = syllable_ranges(left)
+        .iter()
+        .any(|range| range.start == left_start);
+    let right_syllables = syllable_ranges(right);
+    right_syllables
+        .iter()
+        .position(|range| range.end == right_len)
+        .filter(|_| left_aligned)
+        .map(|last| last + 1)
+}
+
+impl Overlap {
+    /// 重なりの深さ。音素の単位で探した場合は音素の数。
+    fn depth(&self) -> usize {
+        self.phoneme_degree.unwrap_or(self.moras.len())
     }
+}
 
-    moras
+/// 発音形での重なりと読みでの重なりを [`ReadingSource::Both`] または [`ReadingSource::Either`] に従って合わせる。
+fn combine_overlaps(
+    pronounciation: Vec<Overlap>,
+    reading: Vec<Overlap>,
+    source: ReadingSource,
+) -> Vec<Overlap> {
+    let require_both = source == ReadingSource::Both;
+
+    pronounciation
+        .into_iter()
+        .merge_join_by(reading, |p, r| p.left.cmp(&r.left))
+        .filter_map(|overlaps| match overlaps {
+            EitherOrBoth::Both(p, r) if p.depth() == r.depth() => Some(Overlap {
+                source: ReadingSource::Both,
+                ..p
+            }),
+            // 両方を要求する場合は浅い方、どちらかでよい場合は深い方を採る
+            EitherOrBoth::Both(p, r) => {
+                let (shallow, deep) = if p.depth() < r.depth() {
+                    (p, r)
+                } else {
+                    (r, p)
+                };
+                if require_both {
+                    Some(Overlap {
+                        source: ReadingSource::Both,
+                        ..shallow
+                    })
+                } else {
+                    Some(deep)
+                }
+            }
+            EitherOrBoth::Left(overlap) | EitherOrBoth::Right(overlap) => {
+                (!require_both).then_some(overlap)
+            }
+        })
+        .collect()
 }
 
+#[cfg(test)]
 fn compute_ary_and_degree<S: AsRef<str>>(pronounciations: &[S]) -> (i32, i32) {
-    let mut ary: i32 = 0;
-    let mut max_degree: i32 = 0;
-
-    for (left, right) in pronounciations
+    let overlaps = find_overlaps(
+        pronounciations,
+        &AnalyzeOptions::default(),
+        ReadingSource::Pronunciation,
+    );
+    let max_degree = overlaps
         .iter()
-        .map(|s| into_moras(s.as_ref()))
-        .tuple_windows()
-    {
-        let degree = (1..=left.len().min(right.len()))
-            .rev()
-            .find(|&d| left[left.len() - d..] == right[..d]);
+        .map(|o| o.moras.len() as i32)
+        .max()
+        .unwrap_or(0);
 
-        if let Some(degree) = degree {
-            max_degree = max_degree.max(degree as i32);
-            ary += 1;
+    (overlaps.len() as i32, max_degree)
+}
+
+fn analyze_tokens(tokens: Vec<Token>, options: &AnalyzeOptions) -> GomamayoResult<Gomamayo> {
+    let mut skipped = vec![];
+    let mut pronounciations = vec![];
+    let mut readings = vec![];
+
+    for (index, token) in tokens.iter().enumerate() {
+        // 発音形と読みの一方しか無い単語では、両者は同じものになる
+        let reading = token.reading_or_pronounciation().map(|r| r.to_string());
+        let pronounciation = match (
+            token.pronounciation_or_reading(),
+            options.unknown_pronounciation,
+        ) {
+            (Some(pronounciation), _) => Some(pronounciation.to_string()),
+            (None, UnknownPronounciationPolicy::Fail) => {
+                return Err(GomamayoError::UnknownPronounciationError(
+                    UnknownPronounciationError {
+                        text: token.surface.clone(),
+                    },
+                ));
+            }
+            (None, UnknownPronounciationPolicy::Skip) => None,
+            (None, UnknownPronounciationPolicy::Surface) => {
+                Some(normalize_kana(&token.surface)).filter(|surface| is_kana(surface))
+            }
+        };
+
+        if pronounciation.is_none() {
+            skipped.push(index);
         }
+
+        // 空の読みはどの単語とも重ならないので、飛ばした単語の前後がつながることはない
+        readings.push(
+            reading
+                .or_else(|| pronounciation.clone())
+                .unwrap_or_default(),
+        );
+        pronounciations.push(pronounciation.unwrap_or_default());
     }
 
-    (ary, max_degree)
-}
+    let mut alternatives_used = vec![];
+    if options.alternative_readings {
+        let primary = match options.reading_source {
+            ReadingSource::Reading => &readings,
+            _ => &pronounciations,
+        };
+        let candidates = tokens
+            .iter()
+            .zip(primary)
+            .map(|(token, primary)| {
+                let mut candidates = vec![primary.clone()];
+                // 飛ばした単語は飛ばしたままにする
+                if primary.is_empty() {
+                    return candidates;
+                }
 
-pub fn analyze(input: &str) -> GomamayoResult<Gomamayo> {
-    let pronounciations = tokenize_to_pronounciations(input)?;
+                let extra = options
+                    .reading_map
+                    .get(&token.surface)
+                    .into_iter()
+                    .flatten();
+                for candidate in token.alternative_pronounciations.iter().chain(extra) {
+                    if !candidates.contains(candidate) {
+                        candidates.push(candidate.clone());
+                    }
+                }
+                candidates
+            })
+            .collect_vec();
 
-    let (ary, degree) = compute_ary_and_degree(&pronounciations);
-    let kind = if ary > 0 {
-        Some(GomamayoKind { ary, degree })
-    } else {
+        for (index, choice) in choose_readings(&candidates, options)
+            .into_iter()
+            .enumerate()
+        {
+            if choice > 0 {
+                pronounciations[index] = candidates[index][choice].clone();
+                readings[index] = candidates[index][choice].clone();
+                alternatives_used.push(index);
+            }
+        }
+    }
+
+    let overlaps = match options.reading_source {
+        ReadingSource::Pronunciation => {
+            find_overlaps(&pronounciations, options, ReadingSource::Pronunciation)
+        }
+        ReadingSource::Reading => find_overlaps(&readings, options, ReadingSource::Reading),
+        source @ (ReadingSource::Both | ReadingSource::Either) => combine_overlaps(
+            find_overlaps(&pronounciations, options, ReadingSource::Pronunciation),
+            find_overlaps(&readings, options, ReadingSource::Reading),
+            source,
+        ),
+    };
+    if options.reading_source == ReadingSource::Reading {
+        pronounciations = readings.clone();
+    }
+
+    let junctions = overlaps
+        .into_iter()
+        .map(|overlap| Junction {
+            left: overlap.left,
+            right: overlap.right,
+            left_start: overlap.left_start,
+            right_end: overlap.right_end,
+            degree: overlap.moras.len() as i32,
+            syllable_degree: overlap.syllable_degree.map(|degree| degree as i32),
+            phoneme_degree: overlap.phoneme_degree.map(|degree| degree as i32),
+            moras: overlap.moras,
+            left_span: tokens[overlap.left_start]
+                .span
+                .to(&tokens[overlap.left].span),
+            right_span: tokens[overlap.right]
+                .span
+                .to(&tokens[overlap.right_end].span),
+            source: overlap.source,
+            loose: overlap.loose,
+            comparison: overlap.comparison,
+        })
+        .collect_vec();
+
+    let kind = if junctions.is_empty() {
         None
+    } else {
+        Some(GomamayoKind {
+            ary: junctions.len() as i32,
+            degree: junctions
+                .iter()
+                .map(|j| match (j.phoneme_degree, options.degree_unit) {
+                    (Some(phoneme_degree), _) => phoneme_degree,
+                    (None, DegreeUnit::Mora) => j.degree,
+                    // 音節の単位で探した重なりは必ず音節の区切りに揃っている
+                    (None, DegreeUnit::Syllable) => j.syllable_degree.unwrap_or(0),
+                })
+                .max()
+                .unwrap_or(0),
+        })
     };
 
     Ok(Gomamayo {
         kind,
         pronounciations,
+        tokens,
+        junctions,
+        skipped,
+        alternatives_used,
     })
 }
 
+/// 各単語の読みの候補 (先頭が本来の読み) から、隣り合う単語の重なりの深さの合計が
+/// 最も大きくなる組み合わせを選ぶ。同じなら別の読みを使う単語が少ないものを選ぶ。
+fn choose_readings(candidates: &[Vec<String>], options: &AnalyzeOptions) -> Vec<usize> {
+    let depth = |left: &str, right: &str| {
+        find_overlaps(&[left, right], options, ReadingSource::Pronunciation)
+            .first()
+            .map_or(0, |overlap| overlap.depth())
+    };
+    let rank = |((depth, alternatives), choice): ((usize, usize), usize)| {
+        (depth, Reverse(alternatives), Reverse(choice))
+    };
+
+    // table[i][c] = (単語 i までで読み c を選んだときの (深さの合計, 別の読みの数), 直前の単語の読み)
+    let mut table: Vec<Vec<((usize, usize), usize)>> = vec![];
+    for (index, readings) in candidates.iter().enumerate() {
+        let row = (0..readings.len())
+            .map(|choice| {
+                let alternative = usize::from(choice > 0);
+                let Some(previous) = table.last() else {
+                    return ((0, alternative), 0);
+                };
+                (0..previous.len())
+                    .map(|previous_choice| {
+                        let ((total, alternatives), _) = previous[previous_choice];
+                        let depth =
+                            depth(&candidates[index - 1][previous_choice], &readings[choice]);
+                        ((total + depth, alternatives + alternative), previous_choice)
+                    })
+                    .max_by_key(|&entry| rank(entry))
+                    .unwrap_or(((0, alternative), 0))
+            })
+            .collect_vec();
+        table.push(row);
+    }
+
+    let Some(last) = table.last() else {
+        return vec![];
+    };
+    let mut choice = (0..last.len())
+        .max_by_key(|&choice| rank((last[choice].0, choice)))
+        .unwrap_or(0);
+    let mut choices = vec![choice];
+    for row in table[1..].iter().rev() {
+        choice = row[choice].1;
+        choices.push(choice);
+    }
+    choices.reverse();
+
+    choices
+}
+
+/// 分かち書き済みの読み (カタカナ) の列からゴママヨを判定する。形態素解析は行わない。
+///
+/// 各単語の表層形は読みそのものとし、位置は読みを連結した文字列の中での位置になる。
+pub fn analyze_pronounciations<S: AsRef<str>>(pronounciations: &[S]) -> Gomamayo {
+    analyze_pronounciations_with(pronounciations, &AnalyzeOptions::default())
+}
+
+/// [`analyze_pronounciations`] を、設定を指定して行う。
+pub fn analyze_pronounciations_with<S: AsRef<str>>(
+    pronounciations: &[S],
+    options: &AnalyzeOptions,
+) -> Gomamayo {
+    let text = pronounciations
+        .iter()
+        .map(|p| p.as_ref())
+        .collect::<String>();
+    let mut byte_start = 0;
+    let tokens = pronounciations
+        .iter()
+        .map(|pronounciation| {
+            let pronounciation = pronounciation.as_ref();
+            let byte_end = byte_start + pronounciation.len();
+            let span = Span::from_byte_range(&text, byte_start..byte_end);
+            byte_start = byte_end;
+
+            Token {
+                surface: pronounciation.to_string(),
+                pronounciation: Some(pronounciation.to_string()),
+                alternative_pronounciations: vec![],
+                reading: None,
+                part_of_speech: vec![],
+                lemma: None,
+                origin: TokenOrigin::Provided,
+                span,
+            }
+        })
+        .collect();
+
+    analyze_tokens(tokens, options).expect("every token has its pronounciation")
+}
+
+/// 分かち書き済みの読みの列から、惜しいゴママヨの候補を探す。
+/// 詳しくは [`Analyzer::near_misses`] を参照。
+pub fn near_misses_in_pronounciations<S: AsRef<str>>(
+    pronounciations: &[S],
+    options: &AnalyzeOptions,
+    max_substitutions: usize,
+) -> Vec<NearMiss> {
+    let gomamayo = analyze_pronounciations_with(pronounciations, options);
+    near_miss::find_near_misses(&gomamayo, options, max_substitutions)
+}
+
+/// 毎回辞書を読み込み直すので、複数の入力を解析する場合は [`Analyzer`] を使い回すこと。
+///
+/// 同梱の辞書を使う。辞書を同梱していない (`no-bundled-dict` だけを有効にした) 場合は
+/// 常に [`GomamayoError::DictionaryNotBundledError`] を返すので、
+/// [`AnalyzerBuilder::dictionary_path`] を指定した [`Analyzer`] を使うこと。
+#[cfg(feature = "lindera")]
+pub fn analyze(input: &str) -> GomamayoResult<Gomamayo> {
+    Analyzer::new()?.analyze(input)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    struct TestCase {
-        input: &'static str,
-        expected_pronounciations: &'static [&'static str],
-        expected_ary: i32,
-        expected_degree: i32,
+    pub(crate) struct TestCase {
+        #[cfg_attr(not(feature = "lindera"), allow(dead_code))]
+        pub input: &'static str,
+        pub expected_pronounciations: &'static [&'static str],
+        pub expected_ary: i32,
+        pub expected_degree: i32,
     }
 
-    const TEST_CASES: &[TestCase] = &[
+    pub(crate) const TEST_CASES: &[TestCase] = &[
         TestCase {
             input: "ゴママヨ",
             expected_pronounciations: &["ゴマ", "マヨ"],
@@ -311,40 +1011,425 @@ mod tests {
     ];
 
     #[test]
-    fn correct_tokenize() {
+    fn correct_ary_degree() {
         for case in TEST_CASES {
+            let (ary, degree) = compute_ary_and_degree(case.expected_pronounciations);
             assert_eq!(
-                tokenize_to_pronounciations(case.input).unwrap(),
-                case.expected_pronounciations,
+                ary, case.expected_ary,
+                "wrong ary for {:?}",
+                case.expected_pronounciations
+            );
+            assert_eq!(
+                degree, case.expected_degree,
+                "wrong degree for {:?}",
+                case.expected_pronounciations
             );
         }
     }
 
     #[test]
-    fn test_into_moras() {
+    fn overlapping_moras() {
+        let overlaps = find_overlaps(
+            &["タイコ", "コーボ", "ボシュー", "シューリョー"],
+            &AnalyzeOptions::default(),
+            ReadingSource::Pronunciation,
+        );
         assert_eq!(
-            into_moras("オレンジジュース"),
-            ["オ", "レ", "ン", "ジ", "ジュ", "ー", "ス"]
+            overlaps
+                .iter()
+                .map(|o| (o.left, o.right, o.moras.clone()))
+                .collect_vec(),
+            [
+                (0, 1, into_moras("コ")),
+                (1, 2, into_moras("ボ")),
+                (2, 3, into_moras("シュー")),
+            ]
         );
-        assert_eq!(into_moras("シャワー"), ["シャ", "ワ", "ー"]);
-        assert_eq!(into_moras("ボシュー"), ["ボ", "シュ", "ー"]);
-        assert_eq!(into_moras("シューリョー"), ["シュ", "ー", "リョ", "ー"]);
     }
 
     #[test]
-    fn correct_ary_degree() {
-        for case in TEST_CASES {
-            let (ary, degree) = compute_ary_and_degree(case.expected_pronounciations);
+    fn span_from_byte_range() {
+        let input = "太鼓公募募集終了";
+        let span = Span::from_byte_range(input, 6..12);
+        assert_eq!(span.chars(), 2..4);
+        assert_eq!(&input[span.bytes()], "公募");
+    }
+
+    #[test]
+    fn analyze_pronounciations_directly() {
+        let gomamayo = analyze_pronounciations(&["タイコ", "コーボ", "ボシュー"]);
+        assert_eq!(gomamayo.kind, Some(GomamayoKind { ary: 2, degree: 1 }));
+        assert_eq!(gomamayo.pronounciations, ["タイコ", "コーボ", "ボシュー"]);
+        assert_eq!(gomamayo.junctions[1].left_span.chars(), 3..6);
+        assert_eq!(gomamayo.junctions[1].right_span.chars(), 6..10);
+    }
+
+    fn token(surface: &str, pronounciation: Option<&str>) -> Token {
+        Token {
+            surface: surface.to_string(),
+            pronounciation: pronounciation.map(|p| p.to_string()),
+            alternative_pronounciations: vec![],
+            reading: None,
+            part_of_speech: vec![],
+            lemma: None,
+            origin: TokenOrigin::Provided,
+            span: Span::from_byte_range(surface, 0..surface.len()),
+        }
+    }
+
+    #[test]
+    fn unknown_pronounciation_policy() {
+        let tokens = vec![
+            token("ゴマ", Some("ゴマ")),
+            token("☆", None),
+            token("マヨ", Some("マヨ")),
+            token("よう", None),
+            token("ウカイ", Some("ウカイ")),
+        ];
+        let with_policy = |policy| {
+            let options = AnalyzeOptions {
+                unknown_pronounciation: policy,
+                ..Default::default()
+            };
+            analyze_tokens(tokens.clone(), &options)
+        };
+
+        assert!(matches!(
+            with_policy(UnknownPronounciationPolicy::Fail),
+            Err(GomamayoError::UnknownPronounciationError(UnknownPronounciationError { text }))
+                if text == "☆"
+        ));
+
+        let skip = with_policy(UnknownPronounciationPolicy::Skip).unwrap();
+        assert_eq!(skip.kind, None);
+        assert_eq!(skip.skipped, [1, 3]);
+        assert_eq!(skip.pronounciations, ["ゴマ", "", "マヨ", "", "ウカイ"]);
+
+        let surface = with_policy(UnknownPronounciationPolicy::Surface).unwrap();
+        assert_eq!(surface.skipped, [1]);
+        assert_eq!(surface.pronounciations[3], "ヨウ");
+        assert_eq!(
+            surface
+                .junctions
+                .iter()
+                .map(|j| (j.left, j.right))
+                .collect_vec(),
+            [(2, 3), (3, 4)]
+        );
+    }
+
+    #[test]
+    fn normalized_comparison() {
+        let gomamayo = analyze_pronounciations(&["ごま", "ﾏﾖ"]);
+        assert_eq!(gomamayo.kind, Some(GomamayoKind { ary: 1, degree: 1 }));
+        assert_eq!(gomamayo.junctions[0].moras, ["マ"]);
+    }
+
+    #[test]
+    fn long_vowel_equivalence() {
+        let degree = |pronounciations: &[&str], long_vowel| {
+            let options = AnalyzeOptions {
+                long_vowel,
+                ..Default::default()
+            };
+            analyze_pronounciations_with(pronounciations, &options)
+                .kind
+                .map(|kind| kind.degree)
+        };
+
+        // ユーザー辞書の読み (コウ) と発音 (コー) が混ざっていても一致する
+        assert_eq!(
+            degree(&["ギンコウ", "コーザ"], LongVowelMode::Literal),
+            None
+        );
+        assert_eq!(
+            degree(&["ギンコウ", "コーザ"], LongVowelMode::Collapse),
+            Some(2)
+        );
+        assert_eq!(
+            degree(&["ハクレイ", "レーム"], LongVowelMode::Collapse),
+            Some(2)
+        );
+        assert_eq!(degree(&["トー", "オリ"], LongVowelMode::Literal), None);
+        assert_eq!(degree(&["トー", "オリ"], LongVowelMode::Expand), Some(1));
+    }
+
+    #[test]
+    fn reading_sources() {
+        let with_reading = |surface: &str, pronounciation: &str, reading: &str| Token {
+            reading: Some(reading.to_string()),
+            ..token(surface, Some(pronounciation))
+        };
+        // 発音形では重ならず、読みでだけ重なる
+        let hakurei_ishi = vec![
+            with_reading("博麗", "ハクレー", "ハクレイ"),
+            with_reading("石", "イシ", "イシ"),
+        ];
+        // 発音形と読みの両方で重なる
+        let hakurei_reimu = vec![
+            with_reading("博麗", "ハクレー", "ハクレイ"),
+            with_reading("霊夢", "レーム", "レイム"),
+        ];
+        let junctions = |tokens: &Vec<Token>, reading_source| {
+            let options = AnalyzeOptions {
+                reading_source,
+                ..Default::default()
+            };
+            analyze_tokens(tokens.clone(), &options)
+                .unwrap()
+                .junctions
+                .iter()
+                .map(|j| (j.degree, j.source))
+                .collect_vec()
+        };
+
+        assert_eq!(junctions(&hakurei_ishi, ReadingSource::Pronunciation), []);
+        assert_eq!(
+            junctions(&hakurei_ishi, ReadingSource::Reading),
+            [(1, ReadingSource::Reading)]
+        );
+        assert_eq!(junctions(&hakurei_ishi, ReadingSource::Both), []);
+        assert_eq!(
+            junctions(&hakurei_ishi, ReadingSource::Either),
+            [(1, ReadingSource::Reading)]
+        );
+        assert_eq!(
+            junctions(&hakurei_reimu, ReadingSource::Both),
+            [(2, ReadingSource::Both)]
+        );
+        assert_eq!(
+            junctions(&hakurei_reimu, ReadingSource::Either),
+            [(2, ReadingSource::Both)]
+        );
+    }
+
+    #[test]
+    fn loose_voicing() {
+        let loose = AnalyzeOptions {
+            ignore_voicing: true,
+            ..Default::default()
+        };
+
+        assert_eq!(analyze_pronounciations(&["カプ", "ブタ"]).kind, None);
+        let gomamayo = analyze_pronounciations_with(&["カプ", "ブタ"], &loose);
+        assert_eq!(gomamayo.kind, Some(GomamayoKind { ary: 1, degree: 1 }));
+        assert!(gomamayo.is_loose());
+        assert_eq!(gomamayo.junctions[0].moras, ["ブ"]);
+
+        // 連濁 (アカ|カネ → アカ|ガネ)
+        let gomamayo = analyze_pronounciations_with(&["アカ", "ガネ"], &loose);
+        assert_eq!(gomamayo.kind, Some(GomamayoKind { ary: 1, degree: 1 }));
+
+        // 濁点を無視しなくても重なるものはゆるくない
+        let gomamayo = analyze_pronounciations_with(&["ジコ", "コーテー"], &loose);
+        assert_eq!(gomamayo.kind, Some(GomamayoKind { ary: 1, degree: 1 }));
+        assert!(!gomamayo.is_loose());
+    }
+
+    #[test]
+    fn vowel_and_consonant_comparison() {
+        let junctions = |pronounciations: &[&str], comparison| {
+            let options = AnalyzeOptions {
+                comparison,
+                ..Default::default()
+            };
+            analyze_pronounciations_with(pronounciations, &options)
+                .junctions
+                .iter()
+                .map(|j| (j.degree, j.comparison))
+                .collect_vec()
+        };
+
+        assert_eq!(junctions(&["タカ", "ナス"], MoraComparison::Exact), []);
+        assert_eq!(
+            junctions(&["タカ", "ナス"], MoraComparison::Vowel),
+            [(1, MoraComparison::Vowel)]
+        );
+        assert_eq!(
+            junctions(&["サカナ", "アサリ"], MoraComparison::Vowel),
+            [(2, MoraComparison::Vowel)]
+        );
+        assert_eq!(
+            junctions(&["ニク", "コメ"], MoraComparison::Consonant),
+            [(1, MoraComparison::Consonant)]
+        );
+        assert_eq!(junctions(&["アオ", "イエ"], MoraComparison::Consonant), []);
+        // モーラ全体が重なっていれば Exact と報告する
+        assert_eq!(
+            junctions(&["ゴマ", "マヨ"], MoraComparison::Vowel),
+            [(1, MoraComparison::Exact)]
+        );
+    }
+
+    #[test]
+    fn phoneme_matching() {
+        let phoneme = AnalyzeOptions {
+            phoneme_matching: true,
+            ..Default::default()
+        };
+        let degrees = |pronounciations: &[&str]| {
+            analyze_pronounciations_with(pronounciations, &phoneme)
+                .junctions
+                .iter()
+                .map(|j| (j.degree, j.phoneme_degree))
+                .collect_vec()
+        };
+
+        assert_eq!(
+            analyze_pronounciations(&["ボシュー", "ユーザー"]).kind,
+            None
+        );
+        // …syuu|yuu… は音素 3 個、モーラ 2 個 (ユー) の重なり
+        assert_eq!(degrees(&["ボシュー", "ユーザー"]), [(2, Some(3))]);
+        assert_eq!(degrees(&["サイレンス", "スズカ"]), [(1, Some(2))]);
+        assert_eq!(degrees(&["ギンコー", "コーザ"]), [(2, Some(3))]);
+        // ジ (zi) とジュ (zyu) は音素の単位では重ならない
+        assert_eq!(degrees(&["オレンジ", "ジュース"]), []);
+
+        // 次数は音素の数で数え、音節の単位の指定は使わない
+        let kind = |options: &AnalyzeOptions| {
+            analyze_pronounciations_with(&["ボシュ", "シューリョー"], options).kind
+        };
+        assert_eq!(kind(&phoneme), Some(GomamayoKind { ary: 1, degree: 3 }));
+        let syllable = AnalyzeOptions {
+            degree_unit: DegreeUnit::Syllable,
+            ..phoneme.clone()
+        };
+        assert_eq!(kind(&syllable), kind(&phoneme));
+        // 音素の重なりがモーラや音節の途中で終われば音節の数は数えない
+        let syllable_degrees = |pronounciations: &[&str]| {
+            analyze_pronounciations_with(pronounciations, &phoneme)
+                .junctions
+                .iter()
+                .map(|j| j.syllable_degree)
+                .collect_vec()
+        };
+        assert_eq!(syllable_degrees(&["ボシュ", "シューリョー"]), [None]);
+        assert_eq!(syllable_degrees(&["ボシュー", "ユーザー"]), [None]);
+        assert_eq!(syllable_degrees(&["ギンコー", "コーザ"]), [Some(1)]);
+    }
+
+    #[test]
+    fn syllable_degree() {
+        let syllable = AnalyzeOptions {
+            degree_unit: DegreeUnit::Syllable,
+            ..Default::default()
+        };
+        let degrees = |pronounciations: &[&str], options: &AnalyzeOptions| {
+            let gomamayo = analyze_pronounciations_with(pronounciations, options);
+            (
+                gomamayo.kind.map(|kind| kind.degree),
+                gomamayo
+                    .junctions
+                    .iter()
+                    .map(|j| (j.degree, j.syllable_degree))
+                    .collect_vec(),
+            )
+        };
+
+        let mora = AnalyzeOptions::default();
+        assert_eq!(
+            degrees(&["ボシュー", "シューリョー"], &mora),
+            (Some(2), vec![(2, Some(1))])
+        );
+        assert_eq!(
+            degrees(&["ボシュー", "シューリョー"], &syllable),
+            (Some(1), vec![(2, Some(1))])
+        );
+        // 音節の途中で終わる重なりは音節の単位では数えない
+        assert_eq!(
+            degrees(&["ボシュ", "シューリョー"], &mora),
+            (Some(1), vec![(1, None)])
+        );
+        assert_eq!(
+            degrees(&["ギンコー", "コーザ"], &mora),
+            (Some(2), vec![(2, Some(1))])
+        );
+        assert_eq!(
+            degrees(&["ガッコー", "コーシャ"], &mora),
+            (Some(2), vec![(2, Some(1))])
+        );
+        assert_eq!(degrees(&["ボシュ", "シューリョー"], &syllable).0, None);
+    }
+
+    #[test]
+    fn phonetic_spelling() {
+        let orthographic = AnalyzeOptions {
+            spelling: SpellingMode::Orthographic,
+            ..Default::default()
+        };
+
+        for pronounciations in [
+            ["ハナヂ", "ジマン"],
+            ["ミカヅ", "ズキン"],
+            ["ヲ", "オニ"],
+            ["ヰ", "イス"],
+            ["ヱ", "エビ"],
+        ] {
             assert_eq!(
-                ary, case.expected_ary,
-                "wrong ary for {:?}",
-                case.expected_pronounciations
+                analyze_pronounciations(&pronounciations).kind,
+                Some(GomamayoKind { ary: 1, degree: 1 }),
+                "{pronounciations:?}"
             );
             assert_eq!(
-                degree, case.expected_degree,
-                "wrong degree for {:?}",
-                case.expected_pronounciations
+                analyze_pronounciations_with(&pronounciations, &orthographic).kind,
+                None,
+                "{pronounciations:?}"
             );
         }
     }
+
+    #[test]
+    fn cross_token_overlap() {
+        let cross_token = AnalyzeOptions {
+            cross_token: true,
+            ..Default::default()
+        };
+
+        // キノコ|ノ|コ の「ノコ」は一モーラの「ノ」を丸ごと覆う
+        assert_eq!(analyze_pronounciations(&["キノコ", "ノ", "コ"]).kind, None);
+        let gomamayo = analyze_pronounciations_with(&["キノコ", "ノ", "コ"], &cross_token);
+        assert_eq!(gomamayo.kind, Some(GomamayoKind { ary: 1, degree: 2 }));
+        let junction = &gomamayo.junctions[0];
+        assert_eq!((junction.left_start, junction.left), (0, 0));
+        assert_eq!((junction.right, junction.right_end), (1, 2));
+        assert_eq!(junction.moras, ["ノ", "コ"]);
+        assert_eq!(junction.right_span.chars(), 3..5);
+
+        // 隣り合う単語の中で収まる重なりは変わらない
+        let gomamayo =
+            analyze_pronounciations_with(&["タイコ", "コーボ", "ボシュー"], &cross_token);
+        assert_eq!(gomamayo.kind, Some(GomamayoKind { ary: 2, degree: 1 }));
+        assert!(gomamayo
+            .junctions
+            .iter()
+            .all(|j| (j.left_start, j.right_end) == (j.left, j.right)));
+    }
+
+    #[test]
+    fn alternative_readings() {
+        let tokens = vec![
+            token("ニッポン", Some("ニッポン")),
+            Token {
+                alternative_pronounciations: vec!["ニッポン".to_string()],
+                ..token("日本", Some("ニホン"))
+            },
+            token("今日", Some("キョー")),
+            token("ニチヨー", Some("ニチヨー")),
+        ];
+        let mut options = AnalyzeOptions::default();
+        assert_eq!(analyze_tokens(tokens.clone(), &options).unwrap().kind, None);
+
+        options.alternative_readings = true;
+        options
+            .reading_map
+            .insert("今日".to_string(), vec!["コンニチ".to_string()]);
+        let gomamayo = analyze_tokens(tokens, &options).unwrap();
+        assert_eq!(gomamayo.kind, Some(GomamayoKind { ary: 2, degree: 4 }));
+        assert_eq!(gomamayo.alternatives_used, [1, 2]);
+        assert_eq!(
+            gomamayo.pronounciations,
+            ["ニッポン", "ニッポン", "コンニチ", "ニチヨー"]
+        );
+    }
 }
@@ -0,0 +1,372 @@
+use std::{ops::Range, path::Path};
+
+use lindera_core::dictionary::Dictionary;
+use lindera_core::mode::{Mode, Penalty};
+use lindera_dictionary::{load_dictionary, load_dictionary_from_kind, DictionaryKind};
+use lindera_tokenizer::{token::Token as LinderaToken, tokenizer::Tokenizer};
+
+use crate::{
+    tokenizer::PronunciationTokenizer, user_dictionary::build_user_dictionary,
+    DictionaryNotBundledError, GomamayoError, GomamayoResult, Span, Token, TokenOrigin,
+};
+
+/// Lindera で使う辞書の種類。
+///
+/// 同梱されていない辞書はビルド済みの辞書のパスを指定して読み込む必要がある
+/// ([`LinderaTokenizer::from_path`])。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LinderaDictionary {
+    UniDic,
+    Ipadic,
+}
+
+impl Default for LinderaDictionary {
+    fn default() -> Self {
+        if cfg!(feature = "unidic") || !cfg!(feature = "ipadic") {
+            LinderaDictionary::UniDic
+        } else {
+            LinderaDictionary::Ipadic
+        }
+    }
+}
+
+/// 辞書の詳細情報のうち、どの列に何が入っているか。
+pub(crate) struct DetailLayout {
+    pub part_of_speech: Range<usize>,
+    pub reading: usize,
+    /// `reading` が語彙素の読みである辞書での活用形の列。活用している語では `reading` を使わない。
+    pub inflection_form: Option<usize>,
+    pub lemma: usize,
+    pub pronounciation: usize,
+}
+
+const UNIDIC_DETAIL_LAYOUT: DetailLayout = DetailLayout {
+    part_of_speech: 0..4,
+    // UniDic の 6 列目は語彙素の読み (書い → カク) で、表層形の読みの列は無い
+    reading: 6,
+    inflection_form: Some(5),
+    lemma: 7,
+    pronounciation: 9,
+};
+
+const IPADIC_DETAIL_LAYOUT: DetailLayout = DetailLayout {
+    part_of_speech: 0..4,
+    reading: 7,
+    inflection_form: None,
+    lemma: 6,
+    pronounciation: 8,
+};
+
+impl LinderaDictionary {
+    /// この辞書がクレートに同梱されているかどうか。
+    pub fn is_bundled(self) -> bool {
+        match self {
+            LinderaDictionary::UniDic => cfg!(feature = "unidic"),
+            LinderaDictionary::Ipadic => cfg!(feature = "ipadic"),
+        }
+    }
+
+    fn kind(self) -> DictionaryKind {
+        match self {
+            LinderaDictionary::UniDic => DictionaryKind::UniDic,
+            LinderaDictionary::Ipadic => DictionaryKind::IPADIC,
+        }
+    }
+
+    pub(crate) fn detail_layout(self) -> &'static DetailLayout {
+        match self {
+            LinderaDictionary::UniDic => &UNIDIC_DETAIL_LAYOUT,
+            LinderaDictionary::Ipadic => &IPADIC_DETAIL_LAYOUT,
+        }
+    }
+}
+
+/// Lindera の分かち書きのモード。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum SegmentationMode {
+    /// 辞書にある最長の語をそのまま使う
+    Normal,
+    /// 長い複合語を分解する
+    #[default]
+    Decompose,
+}
+
+impl SegmentationMode {
+    fn lindera_mode(self) -> Mode {
+        match self {
+            SegmentationMode::Normal => Mode::Normal,
+            SegmentationMode::Decompose => Mode::Decompose(Penalty::default()),
+        }
+    }
+}
+
+/// Lindera による分かち書き。
+pub struct LinderaTokenizer {
+    /// モードごとの分かち書き器。先頭のものが [`PronunciationTokenizer::tokenize`] で使われる。
+    tokenizers: Vec<Tokenizer>,
+    dictionary: LinderaDictionary,
+    /// 辞書にある別の読みを集めるかどうか
+    alternative_readings: bool,
+}
+
+impl LinderaTokenizer {
+    /// 同梱の辞書を使う。`user_dictionary` は Lindera のユーザー辞書形式の CSV 文字列。
+    ///
+    /// `dictionary` が同梱されていなければ [`GomamayoError::DictionaryNotBundledError`] を返す。
+    pub fn new(dictionary: LinderaDictionary, user_dictionary: &str) -> GomamayoResult<Self> {
+        Self::with_modes(
+            dictionary,
+            None,
+            user_dictionary,
+            &[SegmentationMode::default()],
+        )
+    }
+
+    /// `path` にあるビルド済みの辞書を使う。`dictionary` はその辞書の種類。
+    pub fn from_path(
+        dictionary: LinderaDictionary,
+        path: &Path,
+        user_dictionary: &str,
+    ) -> GomamayoResult<Self> {
+        Self::with_modes(
+            dictionary,
+            Some(path),
+            user_dictionary,
+            &[SegmentationMode::default()],
+        )
+    }
+
+    /// `modes` のそれぞれで分かち書きし、[`PronunciationTokenizer::tokenize_candidates`] で
+    /// すべての結果を返す分かち書き器を作る。`path` が `None` なら同梱の辞書を使い、
+    /// `dictionary` が同梱されていなければ [`GomamayoError::DictionaryNotBundledError`] を返す。
+    ///
+    /// 最後のモード以外のモードごとに辞書を複製するので、モードを増やすとその分だけメモリを使う。
+    pub fn with_modes(
+        dictionary: LinderaDictionary,
+        path: Option<&Path>,
+        user_dictionary: &str,
+        modes: &[SegmentationMode],
+    ) -> GomamayoResult<Self> {
+        let system_dictionary: Dictionary = match path {
+            Some(path) => load_dictionary(path.to_owned())?,
+            None if dictionary.is_bundled() => load_dictionary_from_kind(dictionary.kind())?,
+            None => {
+                return Err(GomamayoError::DictionaryNotBundledError(
+                    DictionaryNotBundledError { dictionary },
+                ))
+            }
+        };
+        let user_dictionary = build_user_dictionary(user_dictionary, dictionary)?;
+
+        // 指定が無ければ既定のモード (Decompose) だけを使う
+        let (last_mode, other_modes) = modes
+            .split_last()
+            .unwrap_or((&SegmentationMode::Decompose, &[]));
+        let mut tokenizers = other_modes
+            .iter()
+            .map(|mode| {
+                Tokenizer::new(
+                    system_dictionary.clone(),
+                    Some(user_dictionary.clone()),
+                    mode.lindera_mode(),
+                )
+            })
+            .collect::<Vec<_>>();
+        // 最後のモードには複製せずに渡す
+        tokenizers.push(Tokenizer::new(
+            system_dictionary,
+            Some(user_dictionary),
+            last_mode.lindera_mode(),
+        ));
+
+        Ok(Self {
+            tokenizers,
+            dictionary,
+            alternative_readings: false,
+        })
+    }
+
+    /// 各単語について、表層形が同じ辞書の他の項目の読みを [`Token::alternative_pronounciations`]
+    /// に集めるかどうか。辞書を引き直すので、既定では集めない。
+    pub fn alternative_readings(mut self, alternative_readings: bool) -> Self {
+        self.alternative_readings = alternative_readings;
+        self
+    }
+
+    pub fn dictionary(&self) -> LinderaDictionary {
+        self.dictionary
+    }
+}
+
+/// 表層形が同じ、辞書の他の項目の発音形 (無ければ読み)。
+fn alternative_pronounciations(token: &LinderaToken, layout: &DetailLayout) -> Vec<String> {
+    let text = token.text;
+    let system_entries = token.dictionary.dict.prefix(text);
+    let user_entries = token
+        .user_dictionary
+        .into_iter()
+        .flat_map(|user_dictionary| user_dictionary.dict.prefix(text));
+
+    let mut alternatives: Vec<String> = vec![];
+    for (_, entry) in system_entries
+        .chain(user_entries)
+        .filter(|(length, entry)| *length == text.len() && entry.word_id != token.word_id)
+    {
+        let mut entry_token = LinderaToken::new(
+            text,
+            token.byte_start,
+            token.byte_end,
+            token.position,
+            entry.word_id,
+            token.dictionary,
+            token.user_dictionary,
+        );
+        let details = entry_token.get_details().unwrap_or_default();
+        let pronounciation = details
+            .get(layout.pronounciation)
+            .filter(|value| **value != "*")
+            .map(|value| value.to_string())
+            .or_else(|| surface_reading(&details, layout));
+
+        if let Some(pronounciation) = pronounciation {
+            if !alternatives.contains(&pronounciation) {
+                alternatives.push(pronounciation);
+            }
+        }
+    }
+
+    alternatives
+}
+
+/// 表層形の読み。語彙素の読みしか無い辞書では、活用していない語でだけ読みの列を使う。
+fn surface_reading(details: &[&str], layout: &DetailLayout) -> Option<String> {
+    let column = |index: usize| details.get(index).filter(|value| **value != "*");
+
+    match layout.inflection_form.and_then(column) {
+        Some(_) => None,
+        None => column(layout.reading).map(|value| value.to_string()),
+    }
+}
+
+impl LinderaTokenizer {
+    fn tokenize_with(&self, tokenizer: &Tokenizer, input: &str) -> GomamayoResult<Vec<Token>> {
+        let layout = self.dictionary.detail_layout();
+        let mut tokens = tokenizer.tokenize(input)?;
+
+        let tokens = tokens
+            .iter_mut()
+            .map(|token| {
+                let origin = if token.word_id.is_unknown() {
+                    TokenOrigin::Unknown
+                } else if token.word_id.is_system() {
+                    TokenOrigin::SystemDictionary
+                } else {
+                    TokenOrigin::UserDictionary
+                };
+                let surface = token.text.to_string();
+                let span = Span::from_byte_range(input, token.byte_start..token.byte_end);
+
+                let alternatives = match origin {
+                    TokenOrigin::Unknown => vec![],
+                    _ if !self.alternative_readings => vec![],
+                    _ => alternative_pronounciations(token, layout),
+                };
+
+                // 未知語の場合は詳細が ["UNK"] だけになる
+                let details = match origin {
+                    TokenOrigin::Unknown => vec![],
+                    _ => token.get_details().unwrap_or_default(),
+                };
+                let column = |index: usize| {
+                    details
+                        .get(index)
+                        .filter(|value| **value != "*")
+                        .map(|value| value.to_string())
+                };
+
+                let pronounciation = column(layout.pronounciation);
+                let reading = surface_reading(&details, layout);
+                let alternative_pronounciations = alternatives
+                    .into_iter()
+                    .filter(|alternative| Some(alternative) != pronounciation.as_ref())
+                    .collect();
+
+                Token {
+                    surface,
+                    pronounciation,
+                    alternative_pronounciations,
+                    reading,
+                    part_of_speech: layout.part_of_speech.clone().filter_map(column).collect(),
+                    lemma: column(layout.lemma),
+                    origin,
+                    span,
+                }
+            })
+            .collect();
+
+        Ok(tokens)
+    }
+}
+
+impl PronunciationTokenizer for LinderaTokenizer {
+    fn tokenize(&self, input: &str) -> GomamayoResult<Vec<Token>> {
+        self.tokenize_with(&self.tokenizers[0], input)
+    }
+
+    /// 各モードでの分かち書きの結果を返す。同じ結果になったものは一つにまとめる。
+    fn tokenize_candidates(&self, input: &str) -> GomamayoResult<Vec<Vec<Token>>> {
+        let mut candidates: Vec<Vec<Token>> = vec![];
+        for tokenizer in &self.tokenizers {
+            let tokens = self.tokenize_with(tokenizer, input)?;
+            if !candidates.contains(&tokens) {
+                candidates.push(tokens);
+            }
+        }
+
+        Ok(candidates)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reading_of_inflected_words() {
+        let unidic = |cform: &str, lform: &str| {
+            let details = [
+                "動詞",
+                "一般",
+                "*",
+                "*",
+                "五段-カ行",
+                cform,
+                lform,
+                "書く",
+                "書い",
+                "カイ",
+            ];
+            surface_reading(&details, &UNIDIC_DETAIL_LAYOUT)
+        };
+        // UniDic の読みの列は語彙素の読みなので、活用している語では使わない
+        assert_eq!(unidic("連用形-イ音便", "カク"), None);
+        assert_eq!(unidic("*", "ハクレイ"), Some("ハクレイ".to_string()));
+
+        // IPADIC の読みの列は表層形の読み
+        let details = [
+            "動詞",
+            "自立",
+            "*",
+            "*",
+            "五段・カ行イ音便",
+            "連用タ接続",
+            "書く",
+            "カイ",
+            "カイ",
+        ];
+        assert_eq!(
+            surface_reading(&details, &IPADIC_DETAIL_LAYOUT),
+            Some("カイ".to_string())
+        );
+    }
+}
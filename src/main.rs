@@ -1,30 +1,291 @@
-use std::env;
+use std::{
+    env, fs,
+    io::{self, Read},
+};
 
-use gomamayo::{GomamayoError, GomamayoKind, UnknownPronounciationError};
+use gomamayo::{
+    Analyzer, DictionaryNotBundledError, Gomamayo, GomamayoError, GomamayoKind, LinderaDictionary,
+    Location, ReadingSource, SegmentationMode, UnknownPronounciationError,
+    UnknownPronounciationPolicy,
+};
+
+struct Args {
+    scan: bool,
+    alternative_readings: bool,
+    inline_markup: bool,
+    unknown_pronounciation: Option<UnknownPronounciationPolicy>,
+    reading_source: Option<ReadingSource>,
+    segmentation_modes: Option<Vec<SegmentationMode>>,
+    dictionary: Option<LinderaDictionary>,
+    dictionary_path: Option<String>,
+    user_dictionaries: Vec<String>,
+    inputs: Vec<String>,
+}
+
+/// `--name value` と `--name=value` のどちらの形式でも値を取り出す。
+fn option_value(
+    name: &str,
+    arg: &str,
+    rest: &mut impl Iterator<Item = String>,
+) -> Result<Option<String>, String> {
+    if arg == name {
+        rest.next()
+            .map(Some)
+            .ok_or_else(|| format!("{name} には値を指定してください。"))
+    } else {
+        Ok(arg
+            .strip_prefix(name)
+            .and_then(|value| value.strip_prefix('='))
+            .map(|value| value.to_string()))
+    }
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut scan = false;
+    let mut alternative_readings = false;
+    let mut inline_markup = false;
+    let mut unknown_pronounciation = None;
+    let mut reading_source = None;
+    let mut segmentation_modes = None;
+    let mut dictionary = None;
+    let mut dictionary_path = None;
+    let mut user_dictionaries = vec![];
+    let mut inputs = vec![];
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--scan" {
+            scan = true;
+        } else if arg == "--alternatives" {
+            alternative_readings = true;
+        } else if arg == "--markup" {
+            inline_markup = true;
+        } else if let Some(policy) = option_value("--unknown", &arg, &mut args)? {
+            unknown_pronounciation = Some(match &*policy {
+                "fail" => UnknownPronounciationPolicy::Fail,
+                "skip" => UnknownPronounciationPolicy::Skip,
+                "surface" => UnknownPronounciationPolicy::Surface,
+                _ => return Err(format!("不明な読みの扱いです: {policy}")),
+            });
+        } else if let Some(source) = option_value("--reading", &arg, &mut args)? {
+            reading_source = Some(match &*source {
+                "pronunciation" => ReadingSource::Pronunciation,
+                "reading" => ReadingSource::Reading,
+                "both" => ReadingSource::Both,
+                "either" => ReadingSource::Either,
+                _ => return Err(format!("不明な読みの種類です: {source}")),
+            });
+        } else if let Some(modes) = option_value("--segmentation", &arg, &mut args)? {
+            segmentation_modes = Some(match &*modes {
+                "decompose" => vec![SegmentationMode::Decompose],
+                "normal" => vec![SegmentationMode::Normal],
+                "both" => vec![SegmentationMode::Decompose, SegmentationMode::Normal],
+                _ => return Err(format!("不明な分かち書きのモードです: {modes}")),
+            });
+        } else if let Some(path) = option_value("--user-dict", &arg, &mut args)? {
+            user_dictionaries.push(path);
+        } else if let Some(path) = option_value("--dict-path", &arg, &mut args)? {
+            dictionary_path = Some(path);
+        } else if let Some(name) = option_value("--dict", &arg, &mut args)? {
+            dictionary = Some(match &*name {
+                "unidic" => LinderaDictionary::UniDic,
+                "ipadic" => LinderaDictionary::Ipadic,
+                _ => return Err(format!("不明な辞書です: {name}")),
+            });
+        } else {
+            inputs.push(arg);
+        }
+    }
+
+    Ok(Args {
+        scan,
+        alternative_readings,
+        inline_markup,
+        unknown_pronounciation,
+        reading_source,
+        segmentation_modes,
+        dictionary,
+        dictionary_path,
+        user_dictionaries,
+        inputs,
+    })
+}
 
 fn main() {
-    for input in env::args().skip(1) {
+    let args = match parse_args() {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            return;
+        }
+    };
+
+    let mut builder = Analyzer::builder()
+        .alternative_readings(args.alternative_readings)
+        .inline_markup(args.inline_markup);
+    if let Some(policy) = args.unknown_pronounciation {
+        builder = builder.unknown_pronounciation(policy);
+    }
+    if let Some(source) = args.reading_source {
+        builder = builder.reading_source(source);
+    }
+    if let Some(modes) = &args.segmentation_modes {
+        builder = builder.segmentation_modes(modes.iter().copied());
+    }
+    if let Some(dictionary) = args.dictionary {
+        builder = builder.dictionary(dictionary);
+    }
+    if let Some(path) = &args.dictionary_path {
+        builder = builder.dictionary_path(path);
+    }
+    for path in &args.user_dictionaries {
+        builder = builder.user_dictionary_path(path);
+    }
+    let analyzer = match builder.build() {
+        Ok(analyzer) => analyzer,
+        Err(GomamayoError::DictionaryNotBundledError(DictionaryNotBundledError { dictionary })) => {
+            eprintln!(
+                "Error: 辞書 ({dictionary:?}) は同梱されていません。--dict-path でビルド済みの辞書のパスを指定してください。"
+            );
+            return;
+        }
+        Err(e) => {
+            eprintln!("Error: 辞書を読み込めませんでした: {:?}", e);
+            return;
+        }
+    };
+
+    // 読みの種類を指定したときだけ、どの読みで重なったかを表示する
+    let show_sources = args.reading_source.is_some();
+
+    if args.scan {
+        scan(&analyzer, &args.inputs, show_sources);
+        return;
+    }
+
+    for input in &args.inputs {
         let input = input.trim();
-        let gomamayo = match gomamayo::analyze(input) {
+        let gomamayo = match analyzer.analyze_best(input) {
             Ok(gomamayo) => gomamayo,
-            Err(GomamayoError::LinderaError(e)) => {
-                eprintln!("Error: 入力を分かち書きできませんでした: {:?}。", e);
-                return;
-            }
-            Err(GomamayoError::UnknownPronounciationError(UnknownPronounciationError { text })) => {
-                eprintln!("Error: 単語の読み方を取得できませんでした: {text}");
-                return;
-            }
             Err(e) => {
-                eprintln!("Error: 不明なエラーが発生しました: {:?}", e);
-                return;
+                report_error(input, e);
+                continue;
             }
         };
 
         if let Some(GomamayoKind { ary, degree }) = gomamayo.kind {
-            println!("{input}: {ary}項{degree}次のゴママヨです。",);
+            println!(
+                "{input}: {ary}項{degree}次のゴママヨです。{}{}",
+                describe_sources(&gomamayo, show_sources),
+                describe_alternatives(&gomamayo)
+            );
         } else {
             println!("{input}: ゴママヨではありません。",);
         }
     }
 }
+
+/// 各ファイル (指定が無ければ標準入力) の中のゴママヨを `ファイル名:行:列: 句: 判定` の形式で列挙する。
+///
+/// 行と列は句の中の最初の接合部の位置。
+fn scan(analyzer: &Analyzer, paths: &[String], show_sources: bool) {
+    let stdin = ["-".to_string()];
+    let paths = if paths.is_empty() { &stdin[..] } else { paths };
+
+    for path in paths {
+        let text = if path == "-" {
+            let mut text = String::new();
+            io::stdin().read_to_string(&mut text).map(|_| text)
+        } else {
+            fs::read_to_string(path)
+        };
+        let text = match text {
+            Ok(text) => text,
+            Err(e) => {
+                eprintln!("Error: {path} を読み込めませんでした: {e}");
+                continue;
+            }
+        };
+
+        let report = analyzer.scan(&text);
+        for failure in report.failures {
+            report_error(
+                &format!("{path}:{}:{}", failure.line, failure.column),
+                failure.error,
+            );
+        }
+
+        for m in report.matches {
+            if let Some(GomamayoKind { ary, degree }) = m.gomamayo.kind {
+                let location = m.junction_locations.first().copied().unwrap_or(Location {
+                    line: m.line,
+                    column: m.column,
+                });
+                println!(
+                    "{path}:{}:{}: {}: {ary}項{degree}次のゴママヨです。{}{}",
+                    location.line,
+                    location.column,
+                    m.text,
+                    describe_sources(&m.gomamayo, show_sources),
+                    describe_alternatives(&m.gomamayo)
+                );
+            }
+        }
+    }
+}
+
+/// 各接合部がどの読みで重なっていたかを `(発音形, 読み)` のように列挙する。
+fn describe_sources(gomamayo: &Gomamayo, show_sources: bool) -> String {
+    if !show_sources {
+        return String::new();
+    }
+
+    let sources = gomamayo
+        .junctions
+        .iter()
+        .map(|junction| match junction.source {
+            ReadingSource::Pronunciation => "発音形",
+            ReadingSource::Reading => "読み",
+            _ => "発音形と読み",
+        })
+        .collect::<Vec<_>>();
+
+    format!(" ({})", sources.join(", "))
+}
+
+/// 別の読みを使った単語を `[日本=ニッポン]` のように列挙する。
+fn describe_alternatives(gomamayo: &Gomamayo) -> String {
+    if gomamayo.alternatives_used.is_empty() {
+        return String::new();
+    }
+
+    let readings = gomamayo
+        .alternatives_used
+        .iter()
+        .map(|&index| {
+            format!(
+                "{}={}",
+                gomamayo.tokens[index].surface, gomamayo.pronounciations[index]
+            )
+        })
+        .collect::<Vec<_>>();
+
+    format!(" [{}]", readings.join(", "))
+}
+
+fn report_error(input: &str, e: GomamayoError) {
+    match e {
+        GomamayoError::LinderaError(e) => {
+            eprintln!(
+                "Error: {input}: 入力を分かち書きできませんでした: {:?}。",
+                e
+            );
+        }
+        GomamayoError::UnknownPronounciationError(UnknownPronounciationError { text }) => {
+            eprintln!("Error: {input}: 単語の読み方を取得できませんでした: {text}");
+        }
+        e => {
+            eprintln!("Error: {input}: 不明なエラーが発生しました: {:?}", e);
+        }
+    }
+}
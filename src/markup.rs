@@ -0,0 +1,165 @@
+use std::ops::Range;
+
+use crate::{kana::normalize_kana, Span, Token, TokenOrigin};
+
+/// 注記を取り除いた入力の一部分。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Piece<'a> {
+    /// 分かち書き器に任せる部分
+    Text { text: &'a str, span: Span },
+    /// `博麗《はくれい》` のように読みを指定された一つの単語
+    Ruby {
+        base: &'a str,
+        reading: &'a str,
+        span: Span,
+    },
+}
+
+/// ルビを、指定された読みを持つ単語にする。
+///
+/// 読みが空なら読みの分からない単語として扱う。
+pub(crate) fn ruby_token(base: &str, reading: &str, span: Span) -> Token {
+    let reading = Some(normalize_kana(reading)).filter(|reading| !reading.is_empty());
+    Token {
+        surface: base.to_string(),
+        pronounciation: reading.clone(),
+        alternative_pronounciations: vec![],
+        reading,
+        part_of_speech: vec![],
+        lemma: None,
+        origin: TokenOrigin::Provided,
+        span,
+    }
+}
+
+fn is_boundary(c: char) -> bool {
+    matches!(c, '|' | '/' | '｜')
+}
+
+fn is_kanji(c: char) -> bool {
+    matches!(c, '\u{3400}'..='\u{4DBF}' | '\u{4E00}'..='\u{9FFF}' | '\u{F900}'..='\u{FAFF}' | '々' | '〆' | 'ヶ')
+}
+
+fn push_text<'a>(pieces: &mut Vec<Piece<'a>>, input: &'a str, range: Range<usize>) {
+    if !range.is_empty() {
+        pieces.push(Piece::Text {
+            text: &input[range.clone()],
+            span: Span::from_byte_range(input, range),
+        });
+    }
+}
+
+/// 入力中の注記を解釈する。
+///
+/// - `|`・`/`・`｜` はそこで必ず単語を区切る
+/// - `《読み》` は直前の漢字の列 (無ければ直前の区切りからの全体) の読みを指定する
+///
+/// 閉じていない `《` や、ルビを振る文字の無い `《` はただの文字として扱う。
+pub(crate) fn parse_markup(input: &str) -> Vec<Piece<'_>> {
+    let mut pieces = vec![];
+    // まだ Piece にしていない部分の開始位置
+    let mut text_start = 0;
+
+    let mut index = 0;
+    while let Some(c) = input[index..].chars().next() {
+        let next = index + c.len_utf8();
+
+        if is_boundary(c) {
+            push_text(&mut pieces, input, text_start..index);
+            text_start = next;
+        } else if c == '《' {
+            if let Some(length) = input[next..].find('》') {
+                let pending = &input[text_start..index];
+                let kanji_start = pending
+                    .char_indices()
+                    .rev()
+                    .take_while(|(_, c)| is_kanji(*c))
+                    .last()
+                    .map_or(0, |(i, _)| i);
+                let base_start = text_start + kanji_start;
+
+                if base_start < index {
+                    push_text(&mut pieces, input, text_start..base_start);
+                    pieces.push(Piece::Ruby {
+                        base: &input[base_start..index],
+                        reading: &input[next..next + length],
+                        span: Span::from_byte_range(input, base_start..index),
+                    });
+
+                    text_start = next + length + '》'.len_utf8();
+                    index = text_start;
+                    continue;
+                }
+            }
+        }
+
+        index = next;
+    }
+    push_text(&mut pieces, input, text_start..input.len());
+
+    pieces
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn describe(input: &str) -> Vec<(String, Range<usize>)> {
+        parse_markup(input)
+            .into_iter()
+            .map(|piece| match piece {
+                Piece::Text { text, span } => (text.to_string(), span.chars()),
+                Piece::Ruby {
+                    base,
+                    reading,
+                    span,
+                } => (format!("{base}={reading}"), span.chars()),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn ruby_and_boundaries() {
+        assert_eq!(
+            describe("博麗《はくれい》霊夢《れいむ》"),
+            [
+                ("博麗=はくれい".to_string(), 0..2),
+                ("霊夢=れいむ".to_string(), 8..10)
+            ]
+        );
+        assert_eq!(
+            describe("世話やき狐《きつね》の仙狐さん"),
+            [
+                ("世話やき".to_string(), 0..4),
+                ("狐=きつね".to_string(), 4..5),
+                ("の仙狐さん".to_string(), 10..15)
+            ]
+        );
+        assert_eq!(
+            describe("|サイレンス《さいれんす》/スズカ"),
+            [
+                ("サイレンス=さいれんす".to_string(), 1..6),
+                ("スズカ".to_string(), 14..17)
+            ]
+        );
+        assert_eq!(
+            describe("ゴマ|マヨ｜《"),
+            [
+                ("ゴマ".to_string(), 0..2),
+                ("マヨ".to_string(), 3..5),
+                ("《".to_string(), 6..7)
+            ]
+        );
+        // ルビを振る文字が無ければ読みごと文字として残す
+        assert_eq!(
+            describe("|《ごま》マヨ"),
+            [("《ごま》マヨ".to_string(), 1..7)]
+        );
+        // 空の読みは読みの分からない単語になる
+        assert_eq!(describe("胡麻《》マヨ")[0], ("胡麻=".to_string(), 0..2));
+        assert_eq!(
+            ruby_token("胡麻", "", Span::from_byte_range("胡麻", 0..6)).pronounciation,
+            None
+        );
+    }
+}
@@ -0,0 +1,569 @@
+use std::{fmt, ops::Range};
+
+use icu_normalizer::DecomposingNormalizerBorrowed;
+
+use crate::kana::{normalize_kana, SMALL_KANA};
+
+/// モーラの種類。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum MoraKind {
+    /// 直音 (カ、ガ、パ など)
+    Ordinary,
+    /// 拗音 (キャ、シュ、クヮ など)
+    Contracted,
+    /// 促音 (ッ)
+    Sokuon,
+    /// 撥音 (ン)
+    Hatsuon,
+    /// 長音 (ー)
+    LongVowel,
+    /// 外来音 (ティ、ファ、ヴァ、ウィ、ツァ など)
+    Foreign,
+}
+
+/// 読みの中の一つのモーラ。
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Mora {
+    text: String,
+    kind: MoraKind,
+}
+
+impl Mora {
+    fn new(text: String) -> Self {
+        let kind = classify(&text);
+        Mora { text, kind }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    pub fn kind(&self) -> MoraKind {
+        self.kind
+    }
+
+    /// 母音をア・イ・ウ・エ・オのいずれかで返す。促音・撥音・長音には母音が無い。
+    pub fn vowel(&self) -> Option<char> {
+        match self.kind {
+            MoraKind::Sokuon | MoraKind::Hatsuon | MoraKind::LongVowel => None,
+            _ => self.text.chars().last().and_then(vowel_of),
+        }
+    }
+
+    /// 子音を訓令式のローマ字で返す (「キャ」→ `ky`、「シ」→ `s`、「ファ」→ `f`)。
+    /// 母音だけのモーラは空文字列になり、促音・撥音・長音には子音が無い。
+    pub fn consonant(&self) -> Option<String> {
+        let mut chars = self.text.chars();
+        let first = chars.next()?;
+        let second = chars.next();
+        let base = consonant_of(first)?;
+
+        let consonant = match (first, second) {
+            (_, None) => base.to_string(),
+            (_, Some('ャ' | 'ュ' | 'ョ')) => format!("{base}y"),
+            (_, Some('ヮ')) | ('ク' | 'グ', Some('ァ' | 'ィ' | 'ェ' | 'ォ')) => {
+                format!("{base}w")
+            }
+            ('ウ', Some(_)) => "w".to_string(),
+            ('フ', Some(_)) => "f".to_string(),
+            ('ツ', Some(_)) => "ts".to_string(),
+            ('イ', Some('ェ')) => "y".to_string(),
+            ('シ' | 'ジ' | 'チ', Some('ェ')) => format!("{base}y"),
+            _ => base.to_string(),
+        };
+
+        Some(consonant)
+    }
+
+    /// 濁点・半濁点を取り除いたモーラ (「ガ」「パ」→「カ」「ハ」、「ヴァ」→「ウァ」)。
+    pub fn unvoiced(&self) -> Mora {
+        let text = DecomposingNormalizerBorrowed::new_nfd()
+            .normalize(&self.text)
+            .chars()
+            .filter(|c| !matches!(c, '\u{3099}' | '\u{309A}'))
+            .collect::<String>();
+
+        Mora::new(text)
+    }
+}
+
+/// 段ごとのカナ (小書きのカナを含む)
+const VOWEL_ROWS: &[(char, &str)] = &[
+    ('ア', "アカガサザタダナハバパマヤラワァャヮ"),
+    ('イ', "イキギシジチヂニヒビピミリヰィ"),
+    ('ウ', "ウクグスズツヅヌフブプムユルヴゥュ"),
+    ('エ', "エケゲセゼテデネヘベペメレヱェ"),
+    ('オ', "オコゴソゾトドノホボポモヨロヲォョ"),
+];
+
+fn vowel_of(c: char) -> Option<char> {
+    VOWEL_ROWS
+        .iter()
+        .find(|(_, row)| row.contains(c))
+        .map(|(vowel, _)| *vowel)
+}
+
+/// 行ごとのカナ (小書きのカナを含む)
+const CONSONANT_ROWS: &[(&str, &str)] = &[
+    ("", "アイウエオァィゥェォ"),
+    ("k", "カキクケコ"),
+    ("g", "ガギグゲゴ"),
+    ("s", "サシスセソ"),
+    ("z", "ザジズゼゾ"),
+    ("t", "タチツテト"),
+    ("d", "ダヂヅデド"),
+    ("n", "ナニヌネノ"),
+    ("h", "ハヒフヘホ"),
+    ("b", "バビブベボ"),
+    ("p", "パピプペポ"),
+    ("m", "マミムメモ"),
+    ("y", "ヤユヨャュョ"),
+    ("r", "ラリルレロ"),
+    ("w", "ワヰヱヲヮ"),
+    ("v", "ヴ"),
+];
+
+fn consonant_of(c: char) -> Option<&'static str> {
+    CONSONANT_ROWS
+        .iter()
+        .find(|(_, row)| row.contains(c))
+        .map(|(consonant, _)| *consonant)
+}
+
+/// 母音のローマ字
+fn romanize_vowel(vowel: char) -> &'static str {
+    match vowel {
+        'ア' => "a",
+        'イ' => "i",
+        'ウ' => "u",
+        'エ' => "e",
+        _ => "o",
+    }
+}
+
+/// モーラの列を音素 (訓令式のローマ字の一文字) に分ける。各音素には元のモーラの番号を添える。
+///
+/// 拗音は子音・`y`・母音の三つに、促音は `Q`、撥音は `N` になり、長音は直前の母音を繰り返す。
+pub(crate) fn into_phonemes(moras: &[Mora]) -> Vec<(usize, String)> {
+    let mut phonemes = vec![];
+    let mut previous_vowel = None;
+
+    for (index, mora) in moras.iter().enumerate() {
+        let vowel = mora.vowel().map(romanize_vowel);
+        match (mora.kind(), mora.consonant(), vowel) {
+            (MoraKind::Sokuon, _, _) => phonemes.push((index, "Q".to_string())),
+            (MoraKind::Hatsuon, _, _) => phonemes.push((index, "N".to_string())),
+            (MoraKind::LongVowel, _, _) => {
+                let vowel = previous_vowel.unwrap_or("ー");
+                phonemes.push((index, vowel.to_string()));
+            }
+            (_, Some(consonant), Some(vowel)) => {
+                phonemes.extend(consonant.chars().map(|c| (index, c.to_string())));
+                phonemes.push((index, vowel.to_string()));
+            }
+            // カナ以外の文字はそのまま一つの音素とする
+            _ => phonemes.push((index, mora.text.clone())),
+        }
+
+        // 「ンー」「ッー」の長音は伸ばす母音が無い
+        previous_vowel = match mora.kind() {
+            MoraKind::LongVowel => previous_vowel,
+            _ => vowel,
+        };
+    }
+
+    phonemes
+}
+
+/// モーラのどの部分を比べるか。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum MoraComparison {
+    /// モーラ全体が一致する
+    #[default]
+    Exact,
+    /// 母音だけが一致する (母音ゴママヨ、…カ|ナ…)
+    Vowel,
+    /// 子音だけが一致する (…カ|コ…)
+    Consonant,
+}
+
+impl Mora {
+    /// [`MoraComparison`] に従って比べるときの値。促音・撥音・長音と、子音で比べるときの
+    /// 子音の無いモーラ (ア行) はモーラそのものを比べる。
+    pub(crate) fn comparison_key(&self, comparison: MoraComparison) -> String {
+        let key = match comparison {
+            MoraComparison::Exact => None,
+            MoraComparison::Vowel => self.vowel().map(|vowel| vowel.to_string()),
+            MoraComparison::Consonant => self.consonant().filter(|c| !c.is_empty()),
+        };
+
+        key.unwrap_or_else(|| self.text.clone())
+    }
+}
+
+/// 長音をどう比較するか。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum LongVowelMode {
+    /// 書かれたとおりに比べる (「コー」と「コウ」は別物)
+    #[default]
+    Literal,
+    /// 「ー」を直前のモーラの母音に置き換える (「コー」を「コオ」とみなす)
+    Expand,
+    /// オ段の後の「ウ」とエ段の後の「イ」を「ー」とみなす (「コウ」を「コー」とみなす)
+    Collapse,
+}
+
+/// モーラの列に [`LongVowelMode`] を適用する。
+pub(crate) fn apply_long_vowel_mode(moras: Vec<Mora>, mode: LongVowelMode) -> Vec<Mora> {
+    let mut result: Vec<Mora> = Vec::with_capacity(moras.len());
+
+    for mora in moras {
+        let previous_vowel = result.last().and_then(|previous| previous.vowel());
+        let replaced = match (mode, mora.as_str(), previous_vowel) {
+            (LongVowelMode::Expand, "ー", Some(vowel)) => Mora::new(vowel.to_string()),
+            (LongVowelMode::Collapse, "ウ", Some('オ'))
+            | (LongVowelMode::Collapse, "イ", Some('エ')) => Mora::new("ー".to_string()),
+            _ => mora,
+        };
+
+        result.push(replaced);
+    }
+
+    result
+}
+
+impl fmt::Display for Mora {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+impl PartialEq<str> for Mora {
+    fn eq(&self, other: &str) -> bool {
+        self.text == other
+    }
+}
+
+impl PartialEq<&str> for Mora {
+    fn eq(&self, other: &&str) -> bool {
+        self.text == *other
+    }
+}
+
+/// 拗音の一文字目になるイ段のカナ
+const I_ROW_KANA: &str = "キギシジチヂニヒビピミリ";
+
+/// 小書きのカナと組み合わさらないカナ
+const STANDALONE_KANA: &str = "ッンー";
+
+fn classify(mora: &str) -> MoraKind {
+    let mut chars = mora.chars();
+    let (Some(first), second) = (chars.next(), chars.next()) else {
+        return MoraKind::Ordinary;
+    };
+
+    match (first, second) {
+        ('ッ', None) => MoraKind::Sokuon,
+        ('ン', None) => MoraKind::Hatsuon,
+        ('ー', None) => MoraKind::LongVowel,
+        ('ヴ', _) => MoraKind::Foreign,
+        (_, None) => MoraKind::Ordinary,
+        (first, Some('ャ' | 'ュ' | 'ョ')) if I_ROW_KANA.contains(first) => MoraKind::Contracted,
+        ('ク' | 'グ', Some('ヮ')) => MoraKind::Contracted,
+        (_, Some(_)) => MoraKind::Foreign,
+    }
+}
+
+/// 読みをモーラに区切る。表記の揺れは [`normalize_kana`] でそろえてから区切る。
+///
+/// 小書きのカナ (ャュョァィゥェォヮ) は直前のカナと合わせて一つのモーラになる。
+///
+/// ```
+/// use gomamayo::{into_moras, MoraKind};
+///
+/// let moras = into_moras("ティーチャー");
+/// assert_eq!(moras, ["ティ", "ー", "チャ", "ー"]);
+/// assert_eq!(moras[0].kind(), MoraKind::Foreign);
+/// assert_eq!(moras[2].kind(), MoraKind::Contracted);
+/// ```
+pub fn into_moras(pronounciation: &str) -> Vec<Mora> {
+    let mut moras = vec![];
+    let mut curr = String::new();
+
+    for c in normalize_kana(pronounciation).chars() {
+        let combines = SMALL_KANA.contains(c)
+            && curr
+                .chars()
+                .next()
+                .is_some_and(|first| !STANDALONE_KANA.contains(first));
+        if !combines && !curr.is_empty() {
+            moras.push(Mora::new(curr));
+            curr = String::new();
+        }
+
+        curr.push(c);
+    }
+
+    if !curr.is_empty() {
+        moras.push(Mora::new(curr));
+    }
+
+    moras
+}
+
+/// 次数を数える単位。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum DegreeUnit {
+    /// モーラ (「シュー」は「シュ」「ー」の 2 モーラ)
+    #[default]
+    Mora,
+    /// 音節 (長音・撥音・促音は直前のモーラと合わせて一つの音節になり、「シュー」は 1 音節)
+    Syllable,
+}
+
+/// モーラの列を音節に区切り、各音節に含まれるモーラの範囲を返す。
+pub(crate) fn syllable_ranges(moras: &[Mora]) -> Vec<Range<usize>> {
+    let mut ranges: Vec<Range<usize>> = vec![];
+
+    for (index, mora) in moras.iter().enumerate() {
+        let joins_previous = matches!(
+            mora.kind(),
+            MoraKind::LongVowel | MoraKind::Hatsuon | MoraKind::Sokuon
+        );
+        match ranges.last_mut() {
+            Some(last) if joins_previous => last.end = index + 1,
+            _ => ranges.push(index..index + 1),
+        }
+    }
+
+    ranges
+}
+
+/// 読みを音節に区切る。各音節はモーラの列になる。
+///
+/// ```
+/// use gomamayo::into_syllables;
+///
+/// let syllables = into_syllables("シューリョーッン");
+/// assert_eq!(syllables, [vec!["シュ", "ー"], vec!["リョ", "ー", "ッ", "ン"]]);
+/// ```
+pub fn into_syllables(pronounciation: &str) -> Vec<Vec<Mora>> {
+    let moras = into_moras(pronounciation);
+    syllable_ranges(&moras)
+        .into_iter()
+        .map(|range| moras[range].to_vec())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_into_moras() {
+        assert_eq!(
+            into_moras("オレンジジュース"),
+            ["オ", "レ", "ン", "ジ", "ジュ", "ー", "ス"]
+        );
+        assert_eq!(into_moras("シャワー"), ["シャ", "ワ", "ー"]);
+        assert_eq!(into_moras("ボシュー"), ["ボ", "シュ", "ー"]);
+        assert_eq!(into_moras("シューリョー"), ["シュ", "ー", "リョ", "ー"]);
+        assert_eq!(into_moras("しゅーりょー"), ["シュ", "ー", "リョ", "ー"]);
+        assert_eq!(into_moras("ｼｭｰﾘｮｰ"), ["シュ", "ー", "リョ", "ー"]);
+        assert_eq!(into_moras("クヮシ"), ["クヮ", "シ"]);
+        assert_eq!(into_moras("イッカゲツ"), ["イ", "ッ", "カ", "ゲ", "ツ"]);
+        assert_eq!(into_moras("イッヶゲツ"), ["イ", "ッ", "ケ", "ゲ", "ツ"]);
+    }
+
+    /// (読み, [(モーラ, 種類)])
+    const SEGMENTATION_TABLE: &[(&str, &[(&str, MoraKind)])] = {
+        use MoraKind::*;
+        &[
+            (
+                "ティーチャー",
+                &[
+                    ("ティ", Foreign),
+                    ("ー", LongVowel),
+                    ("チャ", Contracted),
+                    ("ー", LongVowel),
+                ],
+            ),
+            (
+                "ヴァイオリン",
+                &[
+                    ("ヴァ", Foreign),
+                    ("イ", Ordinary),
+                    ("オ", Ordinary),
+                    ("リ", Ordinary),
+                    ("ン", Hatsuon),
+                ],
+            ),
+            ("ヴィ", &[("ヴィ", Foreign)]),
+            ("ヴ", &[("ヴ", Foreign)]),
+            (
+                "ディスク",
+                &[("ディ", Foreign), ("ス", Ordinary), ("ク", Ordinary)],
+            ),
+            ("トゥ", &[("トゥ", Foreign)]),
+            ("ドゥ", &[("ドゥ", Foreign)]),
+            ("ウィ", &[("ウィ", Foreign)]),
+            ("ウェ", &[("ウェ", Foreign)]),
+            ("ウォ", &[("ウォ", Foreign)]),
+            ("ツァ", &[("ツァ", Foreign)]),
+            ("ファ", &[("ファ", Foreign)]),
+            ("テュ", &[("テュ", Foreign)]),
+            ("シェ", &[("シェ", Foreign)]),
+            ("キョ", &[("キョ", Contracted)]),
+            ("ヂャ", &[("ヂャ", Contracted)]),
+            ("グヮ", &[("グヮ", Contracted)]),
+            (
+                "キッテ",
+                &[("キ", Ordinary), ("ッ", Sokuon), ("テ", Ordinary)],
+            ),
+            (
+                "ホンー",
+                &[("ホ", Ordinary), ("ン", Hatsuon), ("ー", LongVowel)],
+            ),
+            // 促音・撥音・長音には小書きのカナは付かない
+            ("ッァ", &[("ッ", Sokuon), ("ァ", Ordinary)]),
+            ("ンョ", &[("ン", Hatsuon), ("ョ", Ordinary)]),
+        ]
+    };
+
+    #[test]
+    fn segmentation_table() {
+        for (pronounciation, expected) in SEGMENTATION_TABLE {
+            let moras = into_moras(pronounciation);
+            assert_eq!(
+                moras
+                    .iter()
+                    .map(|mora| (mora.as_str(), mora.kind()))
+                    .collect::<Vec<_>>(),
+                *expected,
+                "wrong segmentation for {pronounciation}"
+            );
+        }
+    }
+
+    #[test]
+    fn vowels() {
+        let vowels = |text: &str| {
+            into_moras(text)
+                .iter()
+                .map(|mora| mora.vowel())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            vowels("キッチャーン"),
+            [Some('イ'), None, Some('ア'), None, None]
+        );
+        assert_eq!(vowels("ヴォルテュ"), [Some('オ'), Some('ウ'), Some('ウ')]);
+    }
+
+    #[test]
+    fn long_vowel_modes() {
+        let apply = |text: &str, mode| {
+            apply_long_vowel_mode(into_moras(text), mode)
+                .iter()
+                .map(|mora| mora.to_string())
+                .collect::<String>()
+        };
+
+        assert_eq!(apply("コーコウ", LongVowelMode::Literal), "コーコウ");
+        assert_eq!(apply("コーコウ", LongVowelMode::Expand), "コオコウ");
+        assert_eq!(apply("コーコウ", LongVowelMode::Collapse), "コーコー");
+        assert_eq!(apply("シューリョー", LongVowelMode::Expand), "シュウリョオ");
+        assert_eq!(apply("セイケイ", LongVowelMode::Collapse), "セーケー");
+        // ア段・イ段・ウ段の後や、長音の後の母音はそのまま
+        assert_eq!(apply("カイスウ", LongVowelMode::Collapse), "カイスウ");
+        assert_eq!(apply("ンー", LongVowelMode::Expand), "ンー");
+    }
+
+    #[test]
+    fn unvoiced_moras() {
+        let unvoiced = |text: &str| {
+            into_moras(text)
+                .iter()
+                .map(|mora| mora.unvoiced().to_string())
+                .collect::<String>()
+        };
+        assert_eq!(unvoiced("ガパビュヴァッー"), "カハヒュウァッー");
+        assert_eq!(unvoiced("カタカナ"), "カタカナ");
+    }
+
+    #[test]
+    fn consonants() {
+        let consonants = |text: &str| {
+            into_moras(text)
+                .iter()
+                .map(|mora| mora.consonant())
+                .collect::<Vec<_>>()
+        };
+        let some = |consonant: &str| Some(consonant.to_string());
+
+        assert_eq!(
+            consonants("アキャシッン"),
+            [some(""), some("ky"), some("s"), None, None]
+        );
+        assert_eq!(
+            consonants("ファウィツァクヮチェヴォティ"),
+            [
+                some("f"),
+                some("w"),
+                some("ts"),
+                some("kw"),
+                some("ty"),
+                some("v"),
+                some("t")
+            ]
+        );
+    }
+
+    #[test]
+    fn comparison_keys() {
+        let keys = |text: &str, comparison| {
+            into_moras(text)
+                .iter()
+                .map(|mora| mora.comparison_key(comparison))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(keys("カナー", MoraComparison::Vowel), ["ア", "ア", "ー"]);
+        assert_eq!(keys("キョン", MoraComparison::Consonant), ["ky", "ン"]);
+        assert_eq!(keys("アオ", MoraComparison::Consonant), ["ア", "オ"]);
+        assert_eq!(keys("カナ", MoraComparison::Exact), ["カ", "ナ"]);
+    }
+
+    #[test]
+    fn phonemes() {
+        let phonemes = |text: &str| {
+            into_phonemes(&into_moras(text))
+                .into_iter()
+                .map(|(_, phoneme)| phoneme)
+                .collect::<String>()
+        };
+
+        assert_eq!(phonemes("ボシュー"), "bosyuu");
+        assert_eq!(phonemes("キッチャーン"), "kiQtyaaN");
+        assert_eq!(phonemes("ンー"), "Nー");
+        assert_eq!(
+            into_phonemes(&into_moras("キャア")),
+            [
+                (0, "k".to_string()),
+                (0, "y".to_string()),
+                (0, "a".to_string()),
+                (1, "a".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn syllables() {
+        assert_eq!(
+            syllable_ranges(&into_moras("ボシューカン")),
+            [0..1, 1..3, 3..5]
+        );
+        // 先頭の長音は単独で一つの音節になる
+        assert_eq!(syllable_ranges(&into_moras("ーア")), [0..1, 1..2]);
+    }
+}
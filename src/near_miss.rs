@@ -0,0 +1,199 @@
+use itertools::Itertools;
+
+use crate::{
+    comparison_moras, comparison_units, covered_tokens, junction_moras, AnalyzeOptions, Gomamayo,
+    Mora, Span,
+};
+
+/// 一部の単位 (モーラ・音節・音素) が食い違っている、惜しいゴママヨの候補。
+#[derive(Debug, Clone, PartialEq)]
+pub struct NearMiss {
+    /// 左側の単語の番号 (`Gomamayo::tokens` の添字)
+    pub left: usize,
+    /// 右側の単語の番号
+    pub right: usize,
+    /// 比べた左側の末尾のモーラ (単語をまたぐ場合は前の単語のモーラも含む)
+    pub left_moras: Vec<Mora>,
+    /// 比べた右側の先頭のモーラ (単語をまたぐ場合は後の単語のモーラも含む)
+    pub right_moras: Vec<Mora>,
+    /// 比べた単位の数。単位は [`GomamayoKind::degree`](crate::GomamayoKind::degree) と同じもの。
+    pub degree: i32,
+    /// 食い違っている単位の数
+    pub substitutions: usize,
+    /// 似ている度合い (0.0 から 1.0)。一致する単位は 1、濁点・半濁点だけが違う単位は 0.5 として平均する。
+    pub score: f64,
+    /// 比べた左側のモーラがかかる単語の表層形の入力中の位置
+    pub left_span: Span,
+    /// 比べた右側のモーラがかかる単語の表層形の入力中の位置
+    pub right_span: Span,
+}
+
+/// 隣り合う単語の組ごとに、`max_substitutions` 個までの食い違いを許した最良の重なりを探す。
+///
+/// 単位の区切り方や比べ方、単語をまたぐかどうかは [`AnalyzeOptions`] に従い、ゴママヨの判定と
+/// 同じものを使う。食い違いの無い重なりがあればそれを最良とする。そうでなければ似ている度合い
+/// (`score`) の最も高いものを選び、同じなら長い方を選ぶ。似ている単位が一つも無い重なりは候補にしない。
+pub(crate) fn find_near_misses(
+    gomamayo: &Gomamayo,
+    options: &AnalyzeOptions,
+    max_substitutions: usize,
+) -> Vec<NearMiss> {
+    let mut near_misses = vec![];
+    let moras = gomamayo
+        .pronounciations
+        .iter()
+        .map(|pronounciation| comparison_moras(pronounciation, options))
+        .collect_vec();
+
+    for (left_index, right_index) in (0..moras.len()).tuple_windows() {
+        let (left, right) = junction_moras(&moras, left_index, right_index, options);
+        let units = |moras: &[Mora], ignore_voicing| {
+            comparison_units(moras, options, ignore_voicing, options.comparison)
+        };
+        let (left_units, right_units) = (
+            units(&left, options.ignore_voicing),
+            units(&right, options.ignore_voicing),
+        );
+        // 濁点・半濁点だけの違いを見分けるための単位。区切り方が変わる場合 (「ヴ」の音素など) は使わない
+        let (left_unvoiced, right_unvoiced) = (units(&left, true), units(&right, true));
+        let compare_unvoiced =
+            left_unvoiced.len() == left_units.len() && right_unvoiced.len() == right_units.len();
+
+        let best = (1..=left_units.len().min(right_units.len()))
+            .map(|degree| {
+                let first = left_units.len() - degree;
+                // 一致する単位は 2 点、濁点・半濁点だけが違う単位は 1 点
+                let points = (0..degree)
+                    .map(|i| {
+                        if left_units[first + i].key == right_units[i].key {
+                            2
+                        } else if compare_unvoiced
+                            && left_unvoiced[first + i].key == right_unvoiced[i].key
+                        {
+                            1
+                        } else {
+                            0
+                        }
+                    })
+                    .collect_vec();
+                let substitutions = points.iter().filter(|&&point| point < 2).count();
+                let score = points.iter().sum::<usize>() as f64 / (2 * degree) as f64;
+                (degree, substitutions, score)
+            })
+            .filter(|&(_, substitutions, score)| substitutions <= max_substitutions && score > 0.0)
+            .max_by(|a, b| {
+                (a.1 == 0)
+                    .cmp(&(b.1 == 0))
+                    .then(a.2.total_cmp(&b.2))
+                    .then(a.0.cmp(&b.0))
+            });
+
+        if let Some((degree, substitutions, score)) = best {
+            let left_len = left.len() - left_units[left_units.len() - degree].moras.start;
+            let right_len = right_units[degree - 1].moras.end;
+            let (left_start, right_end) =
+                covered_tokens(&moras, left_index, right_index, left_len, right_len);
+            let tokens = &gomamayo.tokens;
+
+            near_misses.push(NearMiss {
+                left: left_index,
+                right: right_index,
+                left_moras: left[left.len() - left_len..].to_vec(),
+                right_moras: right[..right_len].to_vec(),
+                degree: degree as i32,
+                substitutions,
+                score,
+                left_span: tokens[left_start].span.to(&tokens[left_index].span),
+                right_span: tokens[right_index].span.to(&tokens[right_end].span),
+            });
+        }
+    }
+
+    near_misses
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{near_misses_in_pronounciations, AnalyzeOptions, DegreeUnit, MoraComparison};
+
+    #[test]
+    fn near_miss_candidates() {
+        let candidates = |pronounciations: &[&str], max_substitutions| {
+            near_misses_in_pronounciations(
+                pronounciations,
+                &AnalyzeOptions::default(),
+                max_substitutions,
+            )
+            .into_iter()
+            .map(|m| (m.left, m.degree, m.substitutions, m.score))
+            .collect::<Vec<_>>()
+        };
+
+        // 本物のゴママヨは食い違いの無い候補になる
+        assert_eq!(candidates(&["ゴマ", "マヨ"], 0), [(0, 1, 0, 1.0)]);
+        // 濁点だけの違いは半分一致しているとみなす
+        assert_eq!(candidates(&["タイコ", "ゴボー"], 0), []);
+        assert_eq!(candidates(&["タイコ", "ゴボー"], 1), [(0, 1, 1, 0.5)]);
+        // 一モーラ違い
+        assert_eq!(candidates(&["カタナ", "タカラ"], 1), [(0, 2, 1, 0.5)]);
+        assert_eq!(candidates(&["シンリョー", "リョウリ"], 1), [(0, 2, 1, 0.5)]);
+        assert_eq!(candidates(&["ゴマ", "アヨ", "ヨイ"], 1), [(1, 1, 0, 1.0)]);
+        // 長くても食い違いのある重なりより、食い違いの無い重なりを選ぶ
+        assert_eq!(candidates(&["マカマ", "マカヨ"], 1), [(0, 1, 0, 1.0)]);
+    }
+
+    /// ゴママヨの判定と同じ設定で比べ、本物のゴママヨは必ず食い違いの無い候補になる
+    #[test]
+    fn near_miss_candidates_follow_options() {
+        let candidates = |pronounciations: &[&str], options: &AnalyzeOptions| {
+            near_misses_in_pronounciations(pronounciations, options, 0)
+                .into_iter()
+                .map(|m| (m.left, m.degree, m.substitutions, m.score))
+                .collect::<Vec<_>>()
+        };
+
+        let loose = AnalyzeOptions {
+            ignore_voicing: true,
+            ..Default::default()
+        };
+        assert_eq!(candidates(&["カプ", "ブタ"], &loose), [(0, 1, 0, 1.0)]);
+
+        let vowel = AnalyzeOptions {
+            comparison: MoraComparison::Vowel,
+            ..Default::default()
+        };
+        assert_eq!(candidates(&["タカ", "ナス"], &vowel), [(0, 1, 0, 1.0)]);
+
+        // 音節の途中で終わる重なりは音節の単位では候補にならない
+        let syllable = AnalyzeOptions {
+            degree_unit: DegreeUnit::Syllable,
+            ..Default::default()
+        };
+        assert_eq!(candidates(&["ボシュ", "シューリョー"], &syllable), []);
+        assert_eq!(
+            candidates(&["ボシュー", "シューリョー"], &syllable),
+            [(0, 1, 0, 1.0)]
+        );
+
+        let phoneme = AnalyzeOptions {
+            phoneme_matching: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            candidates(&["ボシュー", "ユーザー"], &phoneme),
+            [(0, 3, 0, 1.0)]
+        );
+
+        let cross_token = AnalyzeOptions {
+            cross_token: true,
+            ..Default::default()
+        };
+        let near_misses = near_misses_in_pronounciations(&["キノコ", "ノ", "コ"], &cross_token, 0);
+        assert_eq!(near_misses.len(), 1);
+        assert_eq!(
+            (near_misses[0].degree, near_misses[0].substitutions),
+            (2, 0)
+        );
+        assert_eq!(near_misses[0].right_span.chars(), 3..5);
+    }
+}
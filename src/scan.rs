@@ -0,0 +1,155 @@
+use crate::{Gomamayo, GomamayoError, Span};
+
+/// 文章中の位置。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Location {
+    /// 行番号 (1 始まり)
+    pub line: usize,
+    /// 列番号 (1 始まり、文字単位)
+    pub column: usize,
+}
+
+/// 長い文章中で見つかったゴママヨ一つ分。
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ScanMatch {
+    /// ゴママヨを含む句の開始位置の行番号 (1 始まり)
+    pub line: usize,
+    /// ゴママヨを含む句の開始位置の列番号 (1 始まり、文字単位)
+    pub column: usize,
+    /// ゴママヨを含む句の文章中の位置
+    pub span: Span,
+    pub text: String,
+    /// 句の解析結果。単語や接合部の位置は文章全体の中での位置になっている。
+    pub gomamayo: Gomamayo,
+    /// 各接合部 (`gomamayo.junctions` と同じ順) の左側の単語の開始位置
+    pub junction_locations: Vec<Location>,
+}
+
+/// 解析できずに飛ばした句一つ分。
+#[derive(Debug)]
+pub struct ScanFailure {
+    /// 句の開始位置の行番号 (1 始まり)
+    pub line: usize,
+    /// 句の開始位置の列番号 (1 始まり、文字単位)
+    pub column: usize,
+    /// 句の文章中の位置
+    pub span: Span,
+    pub text: String,
+    pub error: GomamayoError,
+}
+
+/// 文章全体を調べた結果。
+#[derive(Debug, Default)]
+pub struct ScanReport {
+    pub matches: Vec<ScanMatch>,
+    /// 読みの分からない単語があるなどして解析できなかった句
+    pub failures: Vec<ScanFailure>,
+}
+
+/// 文章を句読点・括弧・空白・改行で区切った一つ分。
+pub(crate) struct Phrase<'a> {
+    pub text: &'a str,
+    pub span: Span,
+    pub line: usize,
+    pub column: usize,
+}
+
+/// `markup` が真なら、ルビの注記に使う `《》` では区切らない。
+fn is_phrase_boundary(c: char, markup: bool) -> bool {
+    c.is_whitespace()
+        || "!\"'(),.:;<>?[]{}".contains(c)
+        || "。、，．！？：；「」『』（）［］｛｝【】〈〉〔〕…‥・".contains(c)
+        || (!markup && "《》".contains(c))
+}
+
+pub(crate) fn split_phrases(text: &str, markup: bool) -> Vec<Phrase<'_>> {
+    let mut phrases = vec![];
+    let mut line = 1;
+    let mut column = 1;
+    // 現在の句の (開始バイト位置, 開始文字位置, 行, 列)
+    let mut start = None;
+
+    for (char_index, (byte_index, c)) in text
+        .char_indices()
+        .chain(std::iter::once((text.len(), '\n')))
+        .enumerate()
+    {
+        match (start, is_phrase_boundary(c, markup)) {
+            (None, false) => start = Some((byte_index, char_index, line, column)),
+            (Some((byte_start, char_start, start_line, start_column)), true) => {
+                phrases.push(Phrase {
+                    text: &text[byte_start..byte_index],
+                    span: Span {
+                        byte_start,
+                        byte_end: byte_index,
+                        char_start,
+                        char_end: char_index,
+                    },
+                    line: start_line,
+                    column: start_column,
+                });
+                start = None;
+            }
+            _ => {}
+        }
+
+        if c == '\n' {
+            line += 1;
+            column = 1;
+        } else {
+            column += 1;
+        }
+    }
+
+    phrases
+}
+
+impl Span {
+    /// `origin` を起点とした位置に変換する。
+    pub(crate) fn shifted(&self, origin: &Span) -> Span {
+        Span {
+            byte_start: self.byte_start + origin.byte_start,
+            byte_end: self.byte_end + origin.byte_start,
+            char_start: self.char_start + origin.char_start,
+            char_end: self.char_end + origin.char_start,
+        }
+    }
+}
+
+impl Gomamayo {
+    /// 句の中の位置を文章全体の中の位置に変換する。
+    pub(crate) fn shift_spans(&mut self, origin: &Span) {
+        for token in &mut self.tokens {
+            token.span = token.span.shifted(origin);
+        }
+        for junction in &mut self.junctions {
+            junction.left_span = junction.left_span.shifted(origin);
+            junction.right_span = junction.right_span.shifted(origin);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_into_phrases() {
+        let text = "ゴママヨ、おいしい。\n太鼓公募 募集終了！";
+        let phrases = split_phrases(text, false);
+        assert_eq!(
+            phrases
+                .iter()
+                .map(|p| (p.text, p.line, p.column))
+                .collect::<Vec<_>>(),
+            [
+                ("ゴママヨ", 1, 1),
+                ("おいしい", 1, 6),
+                ("太鼓公募", 2, 1),
+                ("募集終了", 2, 6),
+            ]
+        );
+        assert_eq!(&text[phrases[3].span.bytes()], "募集終了");
+        assert_eq!(phrases[3].span.chars(), 16..20);
+    }
+}
@@ -0,0 +1,81 @@
+use crate::{GomamayoResult, Span, Token, TokenOrigin};
+
+/// 入力文字列を単語に分割し、それぞれの読みを求めるもの。
+pub trait PronunciationTokenizer {
+    fn tokenize(&self, input: &str) -> GomamayoResult<Vec<Token>>;
+
+    /// 分かち書きの候補をすべて返す。先頭は [`PronunciationTokenizer::tokenize`] と同じ結果になる。
+    fn tokenize_candidates(&self, input: &str) -> GomamayoResult<Vec<Vec<Token>>> {
+        Ok(vec![self.tokenize(input)?])
+    }
+}
+
+/// 空白または `/` で区切られた読み (カナ) をそのまま単語とする。主にテスト用。
+///
+/// ```
+/// use gomamayo::{Analyzer, KanaTokenizer};
+///
+/// let analyzer = Analyzer::with_tokenizer(KanaTokenizer);
+/// let gomamayo = analyzer.analyze("ゴマ/マヨ").unwrap();
+/// assert!(gomamayo.kind.is_some());
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct KanaTokenizer;
+
+impl KanaTokenizer {
+    fn is_separator(c: char) -> bool {
+        c.is_whitespace() || c == '/'
+    }
+}
+
+impl PronunciationTokenizer for KanaTokenizer {
+    fn tokenize(&self, input: &str) -> GomamayoResult<Vec<Token>> {
+        let mut tokens = vec![];
+        let mut start = None;
+
+        for (index, c) in input
+            .char_indices()
+            .chain(std::iter::once((input.len(), '/')))
+        {
+            match (start, Self::is_separator(c)) {
+                (None, false) => start = Some(index),
+                (Some(byte_start), true) => {
+                    let surface = &input[byte_start..index];
+                    tokens.push(Token {
+                        surface: surface.to_string(),
+                        pronounciation: Some(surface.to_string()),
+                        alternative_pronounciations: vec![],
+                        reading: None,
+                        part_of_speech: vec![],
+                        lemma: None,
+                        origin: TokenOrigin::Provided,
+                        span: Span::from_byte_range(input, byte_start..index),
+                    });
+                    start = None;
+                }
+                _ => {}
+            }
+        }
+
+        Ok(tokens)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_kana() {
+        let tokens = KanaTokenizer.tokenize(" タイコ コーボ/ボシュー ").unwrap();
+        assert_eq!(
+            tokens
+                .iter()
+                .map(|t| t.surface.as_str())
+                .collect::<Vec<_>>(),
+            ["タイコ", "コーボ", "ボシュー"]
+        );
+        assert_eq!(tokens[1].span.chars(), 5..8);
+        assert_eq!(tokens[2].span.bytes(), 21..33);
+    }
+}
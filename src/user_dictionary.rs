@@ -0,0 +1,227 @@
+use std::{collections::BTreeMap, fs, path::PathBuf};
+
+use byteorder::{LittleEndian, WriteBytesExt};
+use itertools::Itertools;
+use lindera_core::{
+    dictionary::UserDictionary,
+    prefix_dict::PrefixDict,
+    word_entry::{WordEntry, WordId},
+};
+use yada::{builder::DoubleArrayBuilder, DoubleArray};
+
+use crate::{
+    lindera::LinderaDictionary, GomamayoError, GomamayoResult, InvalidUserDictionaryError,
+};
+
+pub(crate) const BUNDLED_USER_DICTIONARY: &str = include_str!("./user_jisyo.csv");
+const USER_WORD_PART_OF_SPEECH: &str = "カスタム名詞";
+
+// Lindera のユーザー辞書の書式に合わせる
+const SIMPLE_USERDIC_FIELDS_NUM: usize = 3;
+const SIMPLE_WORD_COST: i16 = -10000;
+const SIMPLE_CONTEXT_ID: u16 = 0;
+const UNIDIC_DETAILED_USERDIC_FIELDS_NUM: usize = 21;
+const IPADIC_DETAILED_USERDIC_FIELDS_NUM: usize = 13;
+
+/// 同梱のユーザー辞書に追加で読み込むユーザー辞書。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UserDictionarySource {
+    /// Lindera のユーザー辞書形式の CSV ファイル
+    Path(PathBuf),
+    /// Lindera のユーザー辞書形式の CSV 文字列
+    Csv(String),
+    /// (表層形, 発音) の組
+    Words(Vec<(String, String)>),
+}
+
+impl UserDictionarySource {
+    pub(crate) fn to_csv(&self) -> GomamayoResult<String> {
+        match self {
+            UserDictionarySource::Path(path) => Ok(fs::read_to_string(path)?),
+            UserDictionarySource::Csv(csv) => Ok(csv.clone()),
+            UserDictionarySource::Words(words) => Ok(words
+                .iter()
+                .map(|(surface, pronounciation)| {
+                    format!(
+                        "{},{USER_WORD_PART_OF_SPEECH},{}\n",
+                        escape_csv_field(surface),
+                        escape_csv_field(pronounciation)
+                    )
+                })
+                .collect()),
+        }
+    }
+}
+
+fn escape_csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn invalid(message: impl Into<String>) -> GomamayoError {
+    GomamayoError::InvalidUserDictionaryError(InvalidUserDictionaryError {
+        message: message.into(),
+    })
+}
+
+/// CSV 文字列からユーザー辞書を構築する。
+///
+/// Lindera 自身はファイルのパスからしかユーザー辞書を作れないので、同じ処理をメモリ上で行う。
+pub(crate) fn build_user_dictionary(
+    csv: &str,
+    dictionary: LinderaDictionary,
+) -> GomamayoResult<UserDictionary> {
+    let detailed_fields_num = match dictionary {
+        LinderaDictionary::UniDic => UNIDIC_DETAILED_USERDIC_FIELDS_NUM,
+        LinderaDictionary::Ipadic => IPADIC_DETAILED_USERDIC_FIELDS_NUM,
+    };
+
+    let mut rows = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(csv.as_bytes())
+        .records()
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| invalid(e.to_string()))?;
+    rows.sort_by_key(|row| row[0].to_string());
+
+    let mut word_entry_map: BTreeMap<String, Vec<WordEntry>> = BTreeMap::new();
+    let mut words_data = Vec::<u8>::new();
+    let mut words_idx_data = Vec::<u8>::new();
+
+    for (row_id, row) in rows.iter().enumerate() {
+        let (word_cost, cost_id, word_detail) = if row.len() == SIMPLE_USERDIC_FIELDS_NUM {
+            (
+                SIMPLE_WORD_COST,
+                SIMPLE_CONTEXT_ID,
+                simple_word_detail(dictionary, &row[0], &row[1], &row[2]),
+            )
+        } else if row.len() >= detailed_fields_num {
+            let word_cost = row[3]
+                .parse::<i16>()
+                .map_err(|_| invalid(format!("failed to parse word cost: {}", &row[3])))?;
+            let cost_id = row[1]
+                .parse::<u16>()
+                .map_err(|_| invalid(format!("failed to parse left context id: {}", &row[1])))?;
+            let word_detail = row.iter().skip(4).map(|item| item.to_string()).collect();
+
+            (word_cost, cost_id, word_detail)
+        } else {
+            return Err(invalid(format!(
+                "user dictionary should be a CSV with {} or {}+ fields: {}",
+                SIMPLE_USERDIC_FIELDS_NUM,
+                detailed_fields_num,
+                row.iter().join(",")
+            )));
+        };
+
+        word_entry_map
+            .entry(row[0].to_string())
+            .or_default()
+            .push(WordEntry {
+                word_id: WordId(row_id as u32, false),
+                word_cost,
+                cost_id,
+            });
+
+        words_idx_data.write_u32::<LittleEndian>(words_data.len() as u32)?;
+        bincode::serialize_into(&mut words_data, &word_detail)
+            .map_err(|e| invalid(e.to_string()))?;
+    }
+
+    // 値には (先頭のエントリの番号 << 5) | エントリ数 を入れる
+    let mut id = 0u32;
+    let mut keyset: Vec<(&[u8], u32)> = vec![];
+    for (key, word_entries) in &word_entry_map {
+        let len = word_entries.len() as u32;
+        keyset.push((key.as_bytes(), (id << 5) | len));
+        id += len;
+    }
+    let da_bytes = DoubleArrayBuilder::build(&keyset)
+        .ok_or_else(|| invalid("failed to build the double array"))?;
+
+    let mut vals_data = Vec::<u8>::new();
+    for word_entry in word_entry_map.values().flatten() {
+        word_entry.serialize(&mut vals_data)?;
+    }
+
+    Ok(UserDictionary {
+        dict: PrefixDict {
+            da: DoubleArray::new(da_bytes),
+            vals_data,
+            is_system: false,
+        },
+        words_idx_data,
+        words_data,
+    })
+}
+
+/// 表層形・品詞・読みだけの簡易形式の行から、辞書ごとの詳細情報の並びを作る。
+fn simple_word_detail(
+    dictionary: LinderaDictionary,
+    surface: &str,
+    part_of_speech: &str,
+    reading: &str,
+) -> Vec<String> {
+    let (len, base_form) = match dictionary {
+        LinderaDictionary::UniDic => (17, None),
+        LinderaDictionary::Ipadic => (9, Some(6)),
+    };
+    let layout = dictionary.detail_layout();
+
+    let mut detail = vec!["*".to_string(); len];
+    detail[layout.part_of_speech.start] = part_of_speech.to_string();
+    detail[layout.reading] = reading.to_string();
+    if let Some(base_form) = base_form {
+        detail[base_form] = surface.to_string();
+    }
+    detail
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build_from_csv() {
+        let dictionary =
+            build_user_dictionary(BUNDLED_USER_DICTIONARY, LinderaDictionary::UniDic).unwrap();
+        let entries = dictionary.dict.prefix("仙狐さん").collect::<Vec<_>>();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].0, "仙狐".len());
+        assert!(!entries[0].1.word_id.is_system());
+    }
+
+    #[test]
+    fn reject_malformed_rows() {
+        assert!(matches!(
+            build_user_dictionary("仙狐,センコ", LinderaDictionary::UniDic),
+            Err(GomamayoError::InvalidUserDictionaryError(_))
+        ));
+    }
+
+    #[test]
+    fn simple_row_layout() {
+        assert_eq!(
+            simple_word_detail(LinderaDictionary::Ipadic, "仙狐", "カスタム名詞", "センコ"),
+            [
+                "カスタム名詞",
+                "*",
+                "*",
+                "*",
+                "*",
+                "*",
+                "仙狐",
+                "センコ",
+                "*"
+            ]
+        );
+        let unidic =
+            simple_word_detail(LinderaDictionary::UniDic, "仙狐", "カスタム名詞", "センコ");
+        assert_eq!(unidic.len(), 17);
+        assert_eq!(unidic[6], "センコ");
+    }
+}